@@ -139,6 +139,7 @@ function exportMap() {
     var floorText = ""
     var wallsText = ""
     var collisionText = ""
+    var objectsText = ""
 
     var c = false
 
@@ -150,11 +151,12 @@ function exportMap() {
             // Pulls more information from the tile objects for export.
             floorText += j==map.w-1 ? floors.indexOf(map.layers[0][i][j]) : floors.indexOf(map.layers[0][i][j]) + ' '
             wallsText += j==map.w-1 ? walls.indexOf(map.layers[1][i][j]) : walls.indexOf(map.layers[1][i][j]) + ' '
+            objectsText += j==map.w-1 ? map.layers[2][i][j].id : map.layers[2][i][j].id + ' '
             
-            // check if a cell is collidable on any of the three layers
+            // check if a cell is collidable on the floor or wall layer
+            // objects are spawned as entities by the game, which have their own collision
             c = map.layers[0][i][j].hasCollision
             || map.layers[1][i][j].hasCollision
-            || map.layers[2][i][j].hasCollision
 
             // type conversion to integer from boolean
             c = c === true ? 1 : 0
@@ -163,6 +165,7 @@ function exportMap() {
         floorText += '\n'
         wallsText  += '\n'
         collisionText  += '\n'
+        objectsText  += '\n'
     }
 
     // create folder and new files in zip
@@ -170,6 +173,7 @@ function exportMap() {
     folder.file("floor.txt", floorText)
     folder.file("walls.txt", wallsText)
     folder.file("collision.txt", collisionText)
+    folder.file("objects.txt", objectsText)
 
     // download zip blobs asynchronously, pass the generated blob into the callback:
     folder.generateAsync({type: "blob"}).then(function(file) {
@@ -196,11 +200,11 @@ async function importMap() {
     for(f in files) {
         if(files[f].name == "floor.txt") {floor = files[f]}
         if(files[f].name == "walls.txt") {wall = files[f]}
-        // if(files[f].name == "collision.txt") {object = files[f]}
+        if(files[f].name == "objects.txt") {object = files[f]}
     }
 
-    // processFiles asynchronously parses the floor and wall file contents
-    processFiles(floor, wall).then((res) => {
+    // processFiles asynchronously parses the floor, wall and object file contents
+    processFiles(floor, wall, object).then((res) => {
         console.log(res);
         WIDTH = res[0][0].length;
         HEIGHT = res[0].length;
@@ -216,7 +220,8 @@ async function processFiles(f, w) {
     var layers = [];
     
     try {
-        for(arg in arguments) { // for both files (can add more in the future)
+        for(arg in arguments) { // for each file (the objects file is optional)
+            if(arguments[arg] === undefined) {continue}
             var a = [];
             await readFileAsync(arguments[arg]).then((res) => {
                 buf = arrayBufferToString(res);
@@ -228,16 +233,18 @@ async function processFiles(f, w) {
                             a.push(line.map(x => floors[x]));
                         } else if (arg == 1) {
                             a.push(line.map(x => walls[x]));
+                        } else if (arg == 2) {
+                            a.push(line.map(x => x == 0 ? empty : objects[x]));
                         }
                     }
                 });
                 layers.push(a)
             });
         }
-        // temporary fix for object/layer mismatch (creates new empty object layer)
-        // we need to create a new object structure
-        // It is no longer possible to import object layer
-        layers.push(initArray(WIDTH, HEIGHT, empty));
+        // maps exported before objects were saved get an empty object layer
+        if(layers.length < 3) {
+            layers.push(initArray(layers[0][0].length, layers[0].length, empty));
+        }
         return layers;
     
     } catch (err) {
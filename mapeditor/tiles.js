@@ -396,6 +396,48 @@ const floors = [
         "y": 80,
         "hasCollision": false
     },
+    {
+        "name": "ICE",
+        "id": 56,
+        "x": 0,
+        "y": 176,
+        "hasCollision": false
+    },
+    {
+        "name": "SNOW",
+        "id": 57,
+        "x": 16,
+        "y": 176,
+        "hasCollision": false
+    },
+    {
+        "name": "SPIN_UP",
+        "id": 58,
+        "x": 32,
+        "y": 176,
+        "hasCollision": false
+    },
+    {
+        "name": "SPIN_DOWN",
+        "id": 59,
+        "x": 48,
+        "y": 176,
+        "hasCollision": false
+    },
+    {
+        "name": "SPIN_LEFT",
+        "id": 60,
+        "x": 64,
+        "y": 176,
+        "hasCollision": false
+    },
+    {
+        "name": "SPIN_RIGHT",
+        "id": 61,
+        "x": 80,
+        "y": 176,
+        "hasCollision": false
+    },
 ]
 const walls = [
     {
@@ -685,6 +727,41 @@ const walls = [
         "y": 0,
         "hasCollision": true
     },
+    {
+        "name": "FLOWERS",
+        "id": 41,
+        "x": 64,
+        "y": 112,
+        "hasCollision": false
+    },
+    {
+        "name": "LEDGE_DOWN",
+        "id": 42,
+        "x": 96,
+        "y": 176,
+        "hasCollision": false
+    },
+    {
+        "name": "LEDGE_LEFT",
+        "id": 43,
+        "x": 112,
+        "y": 176,
+        "hasCollision": false
+    },
+    {
+        "name": "LEDGE_RIGHT",
+        "id": 44,
+        "x": 128,
+        "y": 176,
+        "hasCollision": false
+    },
+    {
+        "name": "THORNS",
+        "id": 45,
+        "x": 144,
+        "y": 176,
+        "hasCollision": false
+    },
 ]
 const objects = [
     {
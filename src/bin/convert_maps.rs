@@ -0,0 +1,51 @@
+/****************************************************/
+// Description: Command line tool that converts old map folders (dim.txt, floor.txt, walls.txt, collision.txt)
+// zips exported by the map editor and Tiled maps into single json map files next to them,
+// e.g. maps/map0/ becomes maps/map0.json and maps/map3.zip becomes maps/map3.json
+// usage: cargo run --bin convert_maps [map folders, zips or tmx files...]
+// with no arguments, every folder in maps/ that contains a dim.txt is converted
+/****************************************************/
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use rustymon::tilemap::{editor, legacy, tiled};
+
+//finds every old map folder inside the maps directory
+fn find_map_folders() -> Result<Vec<PathBuf>, String> {
+    let mut folders: Vec<PathBuf> = fs::read_dir("maps")
+        .map_err(|e| format!("maps: {}", e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.join("dim.txt").is_file())
+        .collect();
+    folders.sort();
+    Ok(folders)
+}
+
+//converts a single map folder, editor export or Tiled map, writing the new map file next to it
+fn convert(folder: &Path) -> Result<PathBuf, String> {
+    let map = match folder.extension() {
+        Some(ext) if ext == "zip" => editor::read_zip(folder).map_err(|e| e.to_string())?,
+        Some(ext) if ext == "tmx" => tiled::read_tmx(folder).map_err(|e| e.to_string())?,
+        _ => legacy::read_folder(folder)?,
+    };
+    let out = folder.with_extension("json");
+    let json = serde_json::to_string_pretty(&map).map_err(|e| e.to_string())?;
+    fs::write(&out, json + "\n").map_err(|e| format!("{}: {}", out.display(), e))?;
+    Ok(out)
+}
+
+fn main() -> Result<(), String> {
+    let folders: Vec<PathBuf> = match env::args().skip(1).map(PathBuf::from).collect::<Vec<_>>() {
+        folders if folders.is_empty() => find_map_folders()?,
+        folders => folders,
+    };
+
+    for folder in folders {
+        let out = convert(&folder)?;
+        println!("{} -> {}", folder.display(), out.display());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,381 @@
+/****************************************************/
+// Description: Command line tool that checks every map in maps/ for mistakes that would otherwise
+// only show up while playing: invalid tiles and layer sizes, walls without collision, regions that
+// cannot be reached, doors without warps, encounter grass without an encounter zone and broken event scripts
+// usage: cargo run --bin lint_maps [map files...]
+// problems that stop a map from loading are errors and make the tool fail, everything else is a warning
+/****************************************************/
+use std::{
+    collections::{HashMap, VecDeque},
+    env, fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use rustymon::{
+    components::stray::SPECIES,
+    script::{Script, ScriptStep},
+    tilemap::{format::EntitySpawn, CollisionTile, TileLayer, TileMap, WallTile},
+    vec2::Vec2,
+};
+
+//finds every map file inside the maps directory
+fn find_maps() -> Result<Vec<PathBuf>, String> {
+    let mut maps: Vec<PathBuf> = fs::read_dir("maps")
+        .map_err(|e| format!("maps: {}", e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "json" || ext == "zip" || ext == "tmx")
+        })
+        .collect();
+    maps.sort();
+    Ok(maps)
+}
+
+//reads the id of a map from its file name, e.g. maps/map2.json has id 2
+fn map_id(path: &Path) -> Option<i32> {
+    path.file_stem()?.to_str()?.strip_prefix("map")?.parse().ok()
+}
+
+//checks if two paths lead to the same file
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+//finds the files a map is stored in when there is more than one, as the game only ever loads the first
+//returns the map's id and its files in the order the game prefers them, or None if the file is the map's only one
+fn duplicates(path: &Path) -> Option<(i32, Vec<PathBuf>)> {
+    let id = map_id(path)?;
+    let files = TileMap::paths(id);
+    (files.len() > 1 && files.iter().any(|file| same_file(file, path))).then_some((id, files))
+}
+
+//loads a map file, returning every problem that stops it from loading
+//a file not named after a map id, or stored next to another file of the same map that the game loads
+//instead, can never be loaded by the game, so that is a problem too
+fn load(path: &Path) -> Result<TileMap, Vec<String>> {
+    let Some(id) = map_id(path) else {
+        return Err(vec![String::from(
+            "the file name is not a map id, maps must be named like map2.json",
+        )]);
+    };
+    if let Some((_, files)) = duplicates(path).filter(|(_, files)| !same_file(&files[0], path)) {
+        return Err(vec![format!(
+            "map {} is loaded from {} instead, so this file is never used",
+            id,
+            files[0].display()
+        )]);
+    }
+    TileMap::read_file(path)
+        .and_then(|file| TileMap::from_file(id, file, path))
+        .map_err(|e| e.errors.iter().map(|error| error.to_string()).collect())
+}
+
+//returns the tile coordinates of an index into the map's tile lists
+fn coords(map: &TileMap, i: usize) -> (usize, usize) {
+    (i % map.size_x, i / map.size_x)
+}
+
+//walls need collision unless the player is meant to walk through or behind them, like tall grass, roofs and thorns
+fn walls_without_collision(map: &TileMap) -> Vec<String> {
+    let mut warnings = vec![];
+    for (i, collision) in map.collision.iter().enumerate() {
+        if *collision != CollisionTile::None {
+            continue;
+        }
+        let (x, y) = coords(map, i);
+        let solid = map.layers.iter().find_map(|layer| match layer {
+            TileLayer::Wall(tiles) => tiles.get(i).filter(|&&tile| {
+                let properties = &map.properties.wall[tile];
+                tile != WallTile::NONE
+                    && properties.walkable
+                    && properties.encounter.is_none()
+                    && !properties.above_player
+                    && !properties.damaging
+            }),
+            _ => None,
+        });
+        if let Some(tile) = solid {
+            warnings.push(format!("wall {:?} at ({}, {}) has no collision", tile, x, y));
+        }
+    }
+    warnings
+}
+
+//flood fills the walkable tiles from every place the player can appear on the map
+//and reports each walkable region that cannot be reached
+//water counts as walkable, since it can be crossed by riding a water stray
+fn unreachable_regions(map: &TileMap, entrances: &[(i32, i32)]) -> Vec<String> {
+    let walkable = |x: i32, y: i32| !map.check_surf_collision(Vec2::from((x, y)));
+    let mut region = vec![None; map.size_x * map.size_y];
+
+    //fills one region, returning its size
+    let fill = |start: (i32, i32), id: usize, region: &mut Vec<Option<usize>>| {
+        let mut queue = VecDeque::from([start]);
+        let mut size = 0;
+        while let Some((x, y)) = queue.pop_front() {
+            if !walkable(x, y) {
+                continue;
+            }
+            let i = x as usize + y as usize * map.size_x;
+            if region[i].is_some() {
+                continue;
+            }
+            region[i] = Some(id);
+            size += 1;
+            queue.extend([(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]);
+        }
+        size
+    };
+
+    for &entrance in entrances {
+        fill(entrance, 0, &mut region);
+    }
+
+    let mut warnings = vec![];
+    for i in 0..region.len() {
+        let (x, y) = coords(map, i);
+        let (x, y) = (x as i32, y as i32);
+        if region[i].is_none() && walkable(x, y) {
+            let size = fill((x, y), 1, &mut region);
+            warnings.push(format!(
+                "{} walkable tiles around ({}, {}) cannot be reached from a spawn point or warp",
+                size, x, y
+            ));
+        }
+    }
+    warnings
+}
+
+//every door tile should lead somewhere
+fn doors_without_warps(map: &TileMap) -> Vec<String> {
+    let has_warp = |x: usize, y: usize| {
+        map.warps
+            .iter()
+            .any(|warp| (warp.x, warp.y) == (x as i32, y as i32))
+    };
+
+    let mut warnings = vec![];
+    for i in 0..map.size_x * map.size_y {
+        let (x, y) = coords(map, i);
+        let door = map.collision[i] == CollisionTile::Door
+            || map.walls_at(Vec2::from((x as u32, y as u32))).any(|tile| *tile == WallTile::DOOR);
+        if door && !has_warp(x, y) {
+            warnings.push(format!("door at ({}, {}) has no warp", x, y));
+        }
+    }
+    for spawn in map.entities.iter() {
+        if let EntitySpawn::Door { x, y } = *spawn {
+            if !has_warp(x as usize, y as usize) {
+                warnings.push(format!("door object at ({}, {}) has no warp", x, y));
+            }
+        }
+    }
+    warnings
+}
+
+//encounter tiles (such as tall grass) only cause encounters inside an encounter zone that has strays
+//tiles that cannot be walked or surfed on are left out, since they are never stepped on
+fn encounter_tiles_without_zones(map: &TileMap) -> Vec<String> {
+    let mut warnings = vec![];
+    for i in 0..map.size_x * map.size_y {
+        let (x, y) = coords(map, i);
+        let pos = Vec2::from((x as u32, y as u32));
+        let Some(kind) = map.encounter_kind(pos).filter(|_| !map.check_surf_collision(pos)) else {
+            continue;
+        };
+        match map.encounter_zone(pos) {
+            None => warnings.push(format!(
+                "{} encounter tile at ({}, {}) is not in an encounter zone",
+                kind, x, y
+            )),
+            Some(zone) if zone.strays.is_empty() => warnings.push(format!(
+                "{} encounter tile at ({}, {}) is in an encounter zone without strays",
+                kind, x, y
+            )),
+            _ => {}
+        }
+    }
+    warnings
+}
+
+//warps have to lead to a walkable tile of a map that exists
+fn broken_warps(map: &TileMap, maps: &HashMap<i32, TileMap>) -> Vec<String> {
+    let mut warnings = vec![];
+    for warp in map.warps.iter() {
+        match maps.get(&warp.target_map) {
+            None => warnings.push(format!(
+                "warp at ({}, {}) leads to map {}, which does not exist or failed to load",
+                warp.x, warp.y, warp.target_map
+            )),
+            Some(target) if target.check_collision(Vec2::from((warp.target_x, warp.target_y))) => {
+                warnings.push(format!(
+                    "warp at ({}, {}) leads to ({}, {}) on map {}, which is not walkable",
+                    warp.x, warp.y, warp.target_x, warp.target_y, warp.target_map
+                ))
+            }
+            _ => {}
+        }
+    }
+    warnings
+}
+
+//returns every step of a script in the order they are written, including the steps inside choices and branches
+fn flatten_steps(steps: &[ScriptStep]) -> Vec<&ScriptStep> {
+    let mut flat = vec![];
+    for step in steps {
+        flat.push(step);
+        match step {
+            ScriptStep::Choice { options, .. } => {
+                for option in options {
+                    flat.extend(flatten_steps(&option.steps));
+                }
+            }
+            ScriptStep::If { then, otherwise, .. } => {
+                flat.extend(flatten_steps(then));
+                flat.extend(flatten_steps(otherwise));
+            }
+            _ => {}
+        }
+    }
+    flat
+}
+
+//every script run by a map's npcs and triggers has to load, battle strays that exist
+//and only move npcs that are on the map (until the script warps away from it)
+fn broken_scripts(map: &TileMap) -> Vec<String> {
+    let npc_ids: Vec<&str> = map
+        .entities
+        .iter()
+        .filter_map(|spawn| match spawn {
+            EntitySpawn::Npc(npc) => npc.id.as_deref(),
+            _ => None,
+        })
+        .collect();
+    let mut names: Vec<&str> = map
+        .entities
+        .iter()
+        .filter_map(|spawn| match spawn {
+            EntitySpawn::Npc(npc) => npc.script.as_deref(),
+            _ => None,
+        })
+        .chain(map.triggers.iter().map(|trigger| trigger.script.as_str()))
+        .collect();
+    names.sort();
+    names.dedup();
+
+    let mut warnings = vec![];
+    for name in names {
+        let script = match Script::load(name) {
+            Ok(script) => script,
+            Err(e) => {
+                warnings.push(format!("script {} cannot be run: {}", name, e));
+                continue;
+            }
+        };
+        let mut on_map = true;
+        for step in flatten_steps(&script.steps) {
+            match step {
+                ScriptStep::MoveNpc { npc, .. } | ScriptStep::FaceNpc { npc, .. }
+                    if on_map && !npc_ids.contains(&npc.as_str()) =>
+                {
+                    warnings.push(format!("script {} refers to npc \"{}\", which is not on the map", name, npc))
+                }
+                ScriptStep::Battle { strays, .. } => {
+                    for species in strays.iter().filter(|species| !SPECIES.iter().any(|info| info.name == **species)) {
+                        warnings.push(format!("script {} battles unknown species \"{}\"", name, species));
+                    }
+                }
+                ScriptStep::Nickname { species, .. } if !SPECIES.iter().any(|info| info.name == *species) => {
+                    warnings.push(format!("script {} nicknames unknown species \"{}\"", name, species));
+                }
+                ScriptStep::Warp { .. } => on_map = false,
+                _ => {}
+            }
+        }
+    }
+    warnings
+}
+
+fn main() -> Result<ExitCode, String> {
+    let all_maps = find_maps()?;
+    let checked: Vec<PathBuf> = match env::args().skip(1).map(PathBuf::from).collect::<Vec<_>>() {
+        paths if paths.is_empty() => all_maps.clone(),
+        paths => paths,
+    };
+
+    //every map is loaded first so that warps between them can be checked,
+    //even when only some of them are being linted
+    let mut maps: HashMap<i32, TileMap> = HashMap::new();
+    let mut files: Vec<(&PathBuf, i32)> = vec![];
+    let (mut errors, mut warnings) = (0, 0);
+    for path in checked.iter() {
+        //the file the game loads is still linted, but the map shouldn't have other files
+        if let Some((id, files)) = duplicates(path).filter(|(_, files)| same_file(&files[0], path)) {
+            let others: Vec<String> = files[1..].iter().map(|file| file.display().to_string()).collect();
+            println!(
+                "{}: error: map {} is also stored in {}, which the game ignores",
+                path.display(),
+                id,
+                others.join(", ")
+            );
+            errors += 1;
+        }
+        match load(path) {
+            Ok(map) => {
+                files.push((path, map.id));
+                maps.insert(map.id, map);
+            }
+            Err(problems) => {
+                for problem in problems {
+                    println!("{}: error: {}", path.display(), problem);
+                    errors += 1;
+                }
+            }
+        }
+    }
+    for path in all_maps.iter().filter(|path| !checked.contains(path)) {
+        if let Ok(map) = load(path) {
+            maps.entry(map.id).or_insert(map);
+        }
+    }
+
+    //every tile the player can appear on, for each map
+    let mut entrances: HashMap<i32, Vec<(i32, i32)>> = HashMap::new();
+    for map in maps.values() {
+        entrances.entry(map.id).or_default().extend(map.spawn);
+        for warp in map.warps.iter() {
+            entrances
+                .entry(warp.target_map)
+                .or_default()
+                .push((warp.target_x, warp.target_y));
+        }
+    }
+
+    for (path, id) in files {
+        let map = &maps[&id];
+        let mut problems = walls_without_collision(map);
+        if let Some(entrances) = entrances.get(&id) {
+            problems.extend(unreachable_regions(map, entrances));
+        }
+        problems.extend(doors_without_warps(map));
+        problems.extend(encounter_tiles_without_zones(map));
+        problems.extend(broken_warps(map, &maps));
+        problems.extend(broken_scripts(map));
+
+        for problem in problems {
+            println!("{}: warning: {}", path.display(), problem);
+            warnings += 1;
+        }
+    }
+
+    println!("{} errors, {} warnings", errors, warnings);
+    Ok(match errors {
+        0 => ExitCode::SUCCESS,
+        _ => ExitCode::FAILURE,
+    })
+}
@@ -4,19 +4,88 @@
 /****************************************************/
 use std::fmt;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum Item {
     Berry,
+    CaptureCube,
+    Totem,
+    StoneTotem,
+    SpiritTotem,
+    SpiritShard,
+    Moonstone,
 }
 
 pub type ItemList = Vec<(Item, u32)>;
 
+impl Item {
+    //returns what the item usually costs in shops, or None for items that are never sold in shops
+    //totems are only ever given by spirits or found, and materials for them are found too
+    pub fn price(&self) -> Option<u32> {
+        match self {
+            Item::Berry => Some(20),
+            Item::CaptureCube => Some(100),
+            _ => None,
+        }
+    }
+
+    //returns what shops pay for the item, or None for items that can't be sold
+    pub fn sell_price(&self) -> Option<u32> {
+        match self {
+            Item::CaptureCube => None,
+            _ if self.totem_tier().is_some() => None,
+            _ => self.price().map(|price| price / 2),
+        }
+    }
+
+    //returns the item's description, shown in shops
+    pub fn description(&self) -> &'static str {
+        match self {
+            Item::Berry => "A juicy berry picked in the valley. Strays love them.",
+            Item::CaptureCube => "A cube that can capture Strays. A gift from your father.",
+            Item::Totem => "A wooden totem imbued with the power of the spirits. It can catch common Strays.",
+            Item::StoneTotem => "A totem carved in stone. It catches Strays more easily, and rarer ones too.",
+            Item::SpiritTotem => "A totem glowing with spirit energy. No Stray is beyond its reach.",
+            Item::SpiritShard => "A shard of crystallized spirit energy, used to upgrade totems.",
+            Item::Moonstone => "A pale stone that shines under the night sky, used to upgrade totems.",
+        }
+    }
+
+    //returns the tier of a totem, which decides how easily it catches strays and which species it can catch
+    //returns None for items that aren't totems
+    pub fn totem_tier(&self) -> Option<u32> {
+        match self {
+            Item::Totem => Some(1),
+            Item::StoneTotem => Some(2),
+            Item::SpiritTotem => Some(3),
+            _ => None,
+        }
+    }
+
+    //returns the totem this one is upgraded into and the materials used up by the upgrade
+    //returns None for items that aren't totems, or totems of the highest tier
+    pub fn totem_upgrade(&self) -> Option<(Item, &'static [(Item, u32)])> {
+        match self {
+            Item::Totem => Some((Item::StoneTotem, &[(Item::SpiritShard, 3)])),
+            Item::StoneTotem => Some((Item::SpiritTotem, &[(Item::SpiritShard, 5), (Item::Moonstone, 1)])),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for Item {
     //writes the item name
     //takes in a Formatter from std::fmt that tells the function how to format the string
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Item::Berry => write!(f, "Berry"),
+            Item::CaptureCube => write!(f, "Capture Cube"),
+            Item::Totem => write!(f, "Totem"),
+            Item::StoneTotem => write!(f, "Stone Totem"),
+            Item::SpiritTotem => write!(f, "Spirit Totem"),
+            Item::SpiritShard => write!(f, "Spirit Shard"),
+            Item::Moonstone => write!(f, "Moonstone"),
         }
     }
 }
@@ -41,8 +110,64 @@ impl Bag {
             Some(i) => {
                 self.items[i] = (item, self.items[i].1 + amount);
             }
-            _ => self.items.push((item, 1)),
+            _ => self.items.push((item, amount)),
         }
         true
     }
+
+    //returns how many of an item are in the bag
+    pub fn count(&self, item: Item) -> u32 {
+        self.items
+            .iter()
+            .find(|(i, _)| *i == item)
+            .map_or(0, |(_, amount)| *amount)
+    }
+
+    //takes up to an amount of an item out of the bag, removing it from the list once there are none left
+    //returns how many were taken
+    pub fn remove_item(&mut self, item: Item, amount: u32) -> u32 {
+        let Some(i) = self.items.iter().position(|i| i.0 == item) else {
+            return 0;
+        };
+        let taken = amount.min(self.items[i].1);
+        self.items[i].1 -= taken;
+        if self.items[i].1 == 0 {
+            self.items.remove(i);
+        }
+        taken
+    }
+
+    //returns the highest tier totem in the bag, used to catch strays
+    pub fn totem(&self) -> Option<Item> {
+        self.items
+            .iter()
+            .map(|(item, _)| *item)
+            .filter(|item| item.totem_tier().is_some())
+            .max_by_key(|item| item.totem_tier())
+    }
+
+    //upgrades the totem in the bag to the next tier, using up the materials it takes
+    //returns the old and new totem, or a message saying why it can't be upgraded
+    pub fn upgrade_totem(&mut self) -> Result<(Item, Item), String> {
+        let Some(totem) = self.totem() else {
+            return Err(String::from("You don't have a totem to upgrade."));
+        };
+        let Some((upgrade, materials)) = totem.totem_upgrade() else {
+            return Err(format!("Your {} can't be upgraded any further.", totem));
+        };
+        if materials.iter().any(|(item, amount)| self.count(*item) < *amount) {
+            let needed: Vec<String> = materials
+                .iter()
+                .map(|(item, amount)| format!("{} (x{})", item, amount))
+                .collect();
+            return Err(format!("Upgrading your {} takes {}.", totem, needed.join(" and ")));
+        }
+
+        for (item, amount) in materials {
+            self.remove_item(*item, *amount);
+        }
+        self.remove_item(totem, 1);
+        self.add_item(upgrade, 1);
+        Ok((totem, upgrade))
+    }
 }
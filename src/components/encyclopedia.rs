@@ -0,0 +1,40 @@
+/****************************************************/
+// Description: Record of which stray species the player has seen and caught
+/****************************************************/
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Encyclopedia {
+    pub seen: HashSet<String>,
+    pub caught: HashSet<String>,
+}
+
+impl Encyclopedia {
+    //returns a new empty Encyclopedia
+    pub fn new() -> Encyclopedia {
+        Encyclopedia::default()
+    }
+
+    //records that a species has been seen in battle
+    //returns true if this is the first time the species was seen
+    pub fn record_seen(&mut self, species: &str) -> bool {
+        self.seen.insert(species.to_string())
+    }
+
+    //records that a species has been caught, which also counts as having seen it
+    //returns true if this is the first time the species was caught
+    pub fn record_caught(&mut self, species: &str) -> bool {
+        self.record_seen(species);
+        self.caught.insert(species.to_string())
+    }
+
+    pub fn is_seen(&self, species: &str) -> bool {
+        self.seen.contains(species)
+    }
+
+    pub fn is_caught(&self, species: &str) -> bool {
+        self.caught.contains(species)
+    }
+}
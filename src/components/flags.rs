@@ -0,0 +1,48 @@
+/****************************************************/
+// Description: Story flags and counters recording what the player has done, set by event scripts
+// and read by scripts, npc spawns, warps and the quest log
+/****************************************************/
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Default, Clone)]
+pub struct Flags {
+    set: HashSet<String>,
+    counters: HashMap<String, i32>,
+}
+
+impl Flags {
+    //returns a list with no flags set and every counter at 0
+    pub fn new() -> Flags {
+        Flags::default()
+    }
+
+    //sets a flag, such as "talked_to_father"
+    pub fn set(&mut self, flag: &str) {
+        self.set.insert(flag.to_string());
+    }
+
+    //clears a flag, doing nothing if it was not set
+    pub fn clear(&mut self, flag: &str) {
+        self.set.remove(flag);
+    }
+
+    //checks if a flag is set
+    pub fn is_set(&self, flag: &str) -> bool {
+        self.set.contains(flag)
+    }
+
+    //returns the value of a counter, which starts at 0
+    pub fn counter(&self, counter: &str) -> i32 {
+        self.counters.get(counter).copied().unwrap_or(0)
+    }
+
+    //sets a counter to a value
+    pub fn set_counter(&mut self, counter: &str, value: i32) {
+        self.counters.insert(counter.to_string(), value);
+    }
+
+    //adds an amount (which can be negative) to a counter
+    pub fn add_counter(&mut self, counter: &str, amount: i32) {
+        *self.counters.entry(counter.to_string()).or_insert(0) += amount;
+    }
+}
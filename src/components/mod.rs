@@ -4,44 +4,158 @@
 /****************************************************/
 
 //use std::sync::Arc;
+use std::fmt;
 
-use self::bag::Item;
+use hecs::Entity;
+use serde::{Deserialize, Serialize};
 
-use super::vec2::{Direction, Vec2};
+use self::{bag::Item, stray::Stray};
+
+use super::{
+    constants::{LEDGE_HOP_DISTANCE, LEDGE_HOP_HEIGHT},
+    vec2::{Direction, Vec2},
+};
 
 pub mod animation;
 pub mod bag;
+pub mod encyclopedia;
+pub mod flags;
+pub mod party;
 pub mod sprite;
 pub mod stray;
+pub mod wallet;
 
 pub struct Player;
 
+//the items a shopkeeper NPC sells, and their prices
+pub struct Shop {
+    pub stock: Vec<(Item, u32)>,
+}
+
+//a spirit shrine, which heals the party and becomes the player's respawn point when used
+pub struct Shrine;
+
+//where the player returns to after losing a battle, the last shrine they used
+pub struct Respawn {
+    pub map: i32,
+    pub position: Vec2,
+    pub facing: Direction,
+}
+
+//marks entities spawned from map data, which are despawned when the player leaves the map
+pub struct MapEntity;
+
 pub struct GroundItem {
     pub item: Item,
     pub amount: u32,
+    pub flag: String, //story flag set when the item is picked up, so it isn't spawned again
 }
 
 pub struct Npc {
+    pub id: Option<String>,   //name event scripts use to find the NPC
+    pub name: Option<String>, //shown above what the NPC says
     pub says: String,
+    pub script: Option<String>, //event script run when the NPC is talked to, instead of saying something
     pub path: Option<WalkingPath>,
+    pub goal: Option<MoveGoal>, //where the NPC is walking to, which overrides their walking path until it is reached
+    pub idle: IdleBehavior,     //what the NPC does when it has no path to walk
+    pub step_delay: f32,        //time in ms the NPC waits after each step or look around
+    pub wait: f32,              //time in ms left before the NPC's next step
+    pub talking: bool,          //the player is talking to the NPC, which faces them and stays put until the dialogue is closed
+}
+
+impl Npc {
+    //creates an NPC that stands still, or walks its path without stopping
+    pub fn new(says: String, path: Option<WalkingPath>) -> Self {
+        Self {
+            id: None,
+            name: None,
+            says,
+            script: None,
+            path,
+            goal: None,
+            idle: IdleBehavior::Stand,
+            step_delay: 0.0,
+            wait: 0.0,
+            talking: false,
+        }
+    }
+}
+
+pub enum IdleBehavior {
+    Stand,                              //stays where it is, facing the same way
+    Wander { home: Vec2, radius: i32 }, //takes random steps, staying within a radius of tiles around its home
+    LookAround,                         //turns to face a random direction
+}
+
+//a place an NPC finds its own way to, around walls and anything else in the way
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveGoal {
+    Tile(Vec2),     //walks to a tile and stops there
+    Follow(Entity), //keeps walking to stand next to an entity, such as the player
+}
+
+//how an NPC walks through its path
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PathMode {
+    #[default]
+    Loop,     //starts over from the beginning after the last step
+    PingPong, //walks the path backwards after the last step, then forwards again
+    Once,     //stops after the last step
 }
 
 pub struct WalkingPath {
     pub path: Vec<Direction>,
     pub index: usize,
+    pub mode: PathMode,
+    pub backwards: bool, //walking back along a ping-pong path
+    pub finished: bool,  //the last step of a path that is walked once has been taken
 }
 
 impl WalkingPath {
+    //creates a path starting at its first step
+    pub fn new(path: Vec<Direction>, mode: PathMode) -> Self {
+        Self {
+            path,
+            index: 0,
+            mode,
+            backwards: false,
+            finished: false,
+        }
+    }
+
     //returns the direction the NPC should be at the current point on their path
     pub fn direction(&self) -> Direction {
-        self.path[self.index]
+        match self.backwards {
+            true => self.path[self.index].opposite(),
+            false => self.path[self.index],
+        }
     }
 
-    //this function goes to the next direction for the npc walking path or loops around
+    //this function goes to the next direction for the npc walking path, turning around or looping depending on its mode
     pub fn advance(&mut self) {
+        if self.backwards {
+            match self.index {
+                0 => self.backwards = false,
+                _ => self.index -= 1,
+            }
+            return;
+        }
+
         self.index += 1;
         if self.index >= self.path.len() {
-            self.index = 0;
+            match self.mode {
+                PathMode::Loop => self.index = 0,
+                PathMode::PingPong => {
+                    self.index = self.path.len() - 1;
+                    self.backwards = true;
+                }
+                PathMode::Once => {
+                    self.index = self.path.len() - 1;
+                    self.finished = true;
+                }
+            }
         }
     }
 }
@@ -51,6 +165,53 @@ pub struct Position(pub Vec2);
 
 pub struct Collision;
 
+//the player riding a water stray across water tiles
+pub struct Surfing {
+    pub stray: String,   //name of the stray being ridden
+    pub texture: String, //texture of the stray being ridden, drawn under the player
+}
+
+//the lead stray of the player's party, walking one tile behind them in the overworld
+//its sprite is the lead stray's battle sprite, drawn shrunk down like a stray being ridden
+pub struct Follower {
+    pub hidden: bool, //out of sight, waiting on the player's tile while surfing, indoors or with no stray able to walk
+    pub behind: Option<Vec2>, //the last tile the player walked off of, where the stray stands while the player is still
+}
+
+//a wild stray roaming an encounter zone in plain sight, which the player battles by touching it
+//it is drawn from its battle sprite like the stray following the player
+pub struct WildStray {
+    pub stray: Stray,
+    pub zone: usize, //index of the map's encounter zone the stray lives in
+    pub wait: f32,   //time in ms left before the stray's next step, while it hasn't noticed the player
+}
+
+//an energy the spirits have given the player, changing which strays they run into until it wears off
+pub struct Charm {
+    pub kind: CharmKind,
+    pub steps: Option<u32>, //steps left before the charm wears off
+    pub time: Option<f32>,  //ms left before the charm wears off
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CharmKind {
+    Attract, //encounters are more common, and can happen outside the tiles strays hide in
+    Repel,   //no encounters at all
+    Rare,    //rarer strays show up more often
+}
+
+impl fmt::Display for CharmKind {
+    //writes the name of the energy, such as in "The attracting energy wore off."
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CharmKind::Attract => write!(f, "attracting energy"),
+            CharmKind::Repel => write!(f, "repelling energy"),
+            CharmKind::Rare => write!(f, "rare energy"),
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Clone, Copy)]
 pub enum MovingState {
     Idle,
@@ -65,6 +226,8 @@ pub struct MovingEntity {
     pub try_sprinting: bool,
     pub animation_time: f32,
     pub rotation_timer: f32,
+    pub forced: Option<Direction>, //direction the tile underneath pushes the entity in (ice, spin tiles), overriding try_moving
+    pub hop: Option<f32>,          //tiles travelled so far while hopping down a ledge
 }
 
 impl Default for MovingEntity {
@@ -78,6 +241,8 @@ impl Default for MovingEntity {
             try_sprinting: false,
             animation_time: 0.0,
             rotation_timer: 0.0,
+            forced: None,
+            hop: None,
         }
     }
 }
@@ -87,4 +252,15 @@ impl MovingEntity {
     pub fn new() -> Self {
         Self { ..Self::default() }
     }
+
+    //returns how high in pixels the entity is drawn above the ground, while hopping down a ledge
+    pub fn hop_height(&self) -> i32 {
+        match self.hop {
+            Some(travelled) => {
+                let progress = (travelled / LEDGE_HOP_DISTANCE).clamp(0.0, 1.0);
+                (LEDGE_HOP_HEIGHT * (progress * std::f32::consts::PI).sin()).round() as i32
+            }
+            None => 0,
+        }
+    }
 }
@@ -0,0 +1,70 @@
+/****************************************************/
+// Description: The player's party of owned strays, which persists between battles
+/****************************************************/
+use serde::{Deserialize, Serialize};
+
+use super::stray::{Stray, Type};
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Party {
+    pub strays: [Option<Stray>; 4],
+}
+
+impl Party {
+    //creates a new party from up to 4 strays
+    pub fn new(strays: [Option<Stray>; 4]) -> Party {
+        Party { strays }
+    }
+
+    //returns the strays that are able to fight, in party order
+    //fainted strays are left out of the battle but keep their slot
+    pub fn battle_strays(&self) -> [Option<Stray>; 4] {
+        self.strays.clone().map(|stray| stray.filter(|s| s.cur_hp > 0))
+    }
+
+    //returns the lead stray, the first one that has not fainted, which follows the player around
+    pub fn lead(&self) -> Option<&Stray> {
+        self.strays.iter().flatten().find(|stray| stray.cur_hp > 0)
+    }
+
+    //returns the first stray that can carry the player across water, a water stray that has not fainted
+    pub fn surfer(&self) -> Option<&Stray> {
+        self.strays
+            .iter()
+            .flatten()
+            .find(|stray| stray.s_type == Type::Water && stray.cur_hp > 0)
+    }
+
+    //adds a stray to the first empty slot of the party
+    //returns false if the party is already full
+    pub fn add(&mut self, stray: Stray) -> bool {
+        match self.strays.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some(stray);
+                true
+            }
+            None => false,
+        }
+    }
+
+    //restores every stray in the party to full health, including fainted ones
+    pub fn heal(&mut self) {
+        for stray in self.strays.iter_mut().flatten() {
+            stray.cur_hp = stray.hp;
+        }
+    }
+
+    //copies the state of the player's strays back into the party after a battle
+    //takes in the player's strays as they were at the end of the battle, a missing stray has fainted
+    pub fn sync_from_battle(&mut self, battle_strays: &[Option<Stray>; 4]) {
+        for (slot, battle_stray) in self.strays.iter_mut().zip(battle_strays.iter()) {
+            match (slot, battle_stray) {
+                (Some(stray), Some(battle_stray)) if stray.id == battle_stray.id => {
+                    *stray = battle_stray.clone();
+                }
+                (Some(stray), None) => stray.cur_hp = 0,
+                _ => {}
+            }
+        }
+    }
+}
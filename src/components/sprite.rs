@@ -5,6 +5,8 @@
 
 use sdl2::rect::Rect;
 
+use super::bag::Item;
+
 pub struct Sprite {
     pub texture: String,
     pub src: Rect,
@@ -25,11 +27,31 @@ impl Sprite {
         }
     }
 
-    //initializes a berry Sprite
-    pub fn berry() -> Self {
+    //initializes the Sprite of an item lying on the ground
+    //takes in the item, each of which has its own picture in the object sprites
+    pub fn item(item: Item) -> Self {
+        let x = match item {
+            Item::Berry => 32,
+            Item::CaptureCube => 80,
+            Item::Totem => 96,
+            Item::StoneTotem => 112,
+            Item::SpiritTotem => 128,
+            Item::SpiritShard => 144,
+            Item::Moonstone => 160,
+        };
+        Self {
+            texture: String::from("assets/tiles/objectsprites.png"),
+            src: Rect::new(x, 0, 16, 16),
+            shift_x: 0,
+            shift_y: 0,
+        }
+    }
+
+    //creates the sprite of a spirit shrine
+    pub fn shrine() -> Self {
         Self {
             texture: String::from("assets/tiles/objectsprites.png"),
-            src: Rect::new(32, 0, 16, 16),
+            src: Rect::new(64, 0, 16, 16),
             shift_x: 0,
             shift_y: 0,
         }
@@ -4,9 +4,29 @@
 // also contains defintions for all individual strays, moves, types, and kinds of moves
 /****************************************************/
 
-use std::{clone::Clone};
+use std::{
+    clone::Clone,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+use rand::Rng;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::constants::TOTEM_CATCH_RATES;
+
+//id given to the next stray created, counting up so that no two strays ever share one
+//strays read back from a save move it past their own ids, so new strays never reuse them
+static NEXT_STRAY_ID: AtomicU64 = AtomicU64::new(1);
+
+//reads the id of a saved stray, reserving it so that no stray created afterwards is given the same one
+fn reserve_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let id = u64::deserialize(deserializer)?;
+    NEXT_STRAY_ID.fetch_max(id.saturating_add(1), Ordering::Relaxed);
+    Ok(id)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Type {
     //enum for identifying the types of certain moves and strays
     Zen,
@@ -20,14 +40,118 @@ pub enum Type {
     Light,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+//how a wild stray roaming the overworld acts when it sees the player
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Temperament {
+    Calm,       //wanders around, paying the player no attention
+    Timid,      //runs away from the player
+    Aggressive, //charges at the player
+}
+
+pub struct SpeciesInfo {
+    //encyclopedia information shared by every stray of a species
+    pub name: &'static str,
+    pub s_type: Type,
+    pub texture: &'static str,
+    pub habitat: &'static str,
+    pub description: &'static str,
+    pub totem_tier: u32, //lowest tier of totem that can catch the species
+    pub temperament: Temperament, //how wild strays of the species act towards the player in the overworld
+}
+
+//every species in the game, in encyclopedia order
+pub const SPECIES: [SpeciesInfo; 9] = [
+    SpeciesInfo {
+        name: "Bitaxum",
+        s_type: Type::Earth,
+        texture: "assets/strays/bitaxum.png",
+        habitat: "Forest soil",
+        description: "A rocky bug stray, bitaxa are sometimes indistinguishable from the soil below. They move slow, dragging their bodies against the ground clumsily, occasionally eating small soft critters from the dirt. A bitaxum that lives for long enough will inevitably find plant life attached to it, living through symbyosis with the bitaxum.",
+        totem_tier: 1,
+        temperament: Temperament::Calm,
+    },
+    SpeciesInfo {
+        name: "Palliub",
+        s_type: Type::Water,
+        texture: "assets/strays/palliub.png",
+        habitat: "Sea surface",
+        description: "A sleak and smooth stray that glides through water as the webbing on its sides slices through the currents. Pallia appear to float like ghosts through the seas. They are found closer to the surface of the water, and, if one is lucky, one might see a palliub or pack of pallia leap out of the water.",
+        totem_tier: 1,
+        temperament: Temperament::Timid,
+    },
+    SpeciesInfo {
+        name: "Rubridum",
+        s_type: Type::Fire,
+        texture: "assets/strays/rubridum.png",
+        habitat: "Mountain skies",
+        description: "An aggressive flying stray, emboldened by the extended and tough beak the protrudes from its head. The long plumes on its head serve, oftentimes, as a distraction to the other strays. It is uncommon to see more than one rubrida at a time, unless you are lucky enough to find them mating.",
+        totem_tier: 2,
+        temperament: Temperament::Aggressive,
+    },
+    SpeciesInfo {
+        name: "Aeternisc",
+        s_type: Type::Time,
+        texture: "assets/strays/aeternisc.png",
+        habitat: "Unknown",
+        description: "An immortal stray that lives forever by periodically restarting its life cycle. It is extremely elusive as one of the two known time type strays, and at the same time highly sought after. It floats in the air, though its tentacles move as if it were in water.",
+        totem_tier: 3,
+        temperament: Temperament::Calm,
+    },
+    SpeciesInfo {
+        name: "Solikigoi",
+        s_type: Type::Water,
+        texture: "assets/strays/solikigoi.png",
+        habitat: "Rivers",
+        description: "An extremely large and vibrant fish stray. Solikigois can frequently be found in rivers, taking up a significant portion of the width of the river, feeding off of the smaller water strays it pushes up in its way. One will commonly find solikigois with damaged dorsal fins, as they tend to be weaker tissue and tend to stick far out of the water, waving in the wind.",
+        totem_tier: 2,
+        temperament: Temperament::Timid,
+    },
+    SpeciesInfo {
+        name: "Catis",
+        s_type: Type::Earth,
+        texture: "assets/strays/catis.png",
+        habitat: "Forest floor",
+        description: "A very slow stray with one of the largest mouth to body ratios of the earth types. Catii have very weak teeth and tend to eat bugs and other small strays off of the ground, sucking them into its large mouth. Sometimes they can be found sitting motionless, mouth wide open on the ground, seducing small strays to walk into its mouth.",
+        totem_tier: 1,
+        temperament: Temperament::Timid,
+    },
+    SpeciesInfo {
+        name: "Cespae",
+        s_type: Type::Zen,
+        texture: "assets/strays/cespae.png",
+        habitat: "Meadows",
+        description: "Cespae are cervids with three sets of horns. Their primary horns on their head server a more offensive purpose than the more defensive sets on their neck and rear. They are very agile and spry, able to contort their torsos in many directions.",
+        totem_tier: 1,
+        temperament: Temperament::Calm,
+    },
+    SpeciesInfo {
+        name: "Omikae",
+        s_type: Type::Astral,
+        texture: "assets/strays/omikae.png",
+        habitat: "Night sky",
+        description: "A nebulous being that's limbs are connected through wisps of gas to the torso. Various temperatures of gas swirl throughout omikae, resulting in different colors throughout the stray.",
+        totem_tier: 2,
+        temperament: Temperament::Aggressive,
+    },
+    SpeciesInfo {
+        name: "Carerus",
+        s_type: Type::Dark,
+        texture: "assets/strays/carerus.png",
+        habitat: "Dark woods",
+        description: "Carerae are feline strays with translucent skin, allowing their skeletal structure to be seen at all times. Some say this is to evoke fear of death in their predators, while it is also probably that their translucent skin can make them seem smaller and more approachable to some prey.",
+        totem_tier: 2,
+        temperament: Temperament::Aggressive,
+    },
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MoveKind {
     //enum listing the kinds of moves (could later be expanded to include things like DOT, different kinds of AOE, etc.)
     Healing,
     Damage,
 }
 
-#[derive(Clone,Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Move {
     //struct defining everything contained within a given move, using the type enum to define the type of the move
     pub name: String,
@@ -195,9 +319,41 @@ impl Move {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StatVariation {
+    //small per-individual bonus rolled once for every stray, added on top of the species base stats
+    pub hp: i32,
+    pub atk: i32,
+    pub def: i32,
+    pub spd: i32,
+}
+
+impl StatVariation {
+    //largest bonus a single stat can roll
+    pub const MAX: i32 = 5;
+
+    //rolls a new random variation for an individual stray
+    pub fn random() -> Self {
+        let mut rng = rand::thread_rng();
+        Self {
+            hp: rng.gen_range(0..=Self::MAX),
+            atk: rng.gen_range(0..=Self::MAX),
+            def: rng.gen_range(0..=Self::MAX),
+            spd: rng.gen_range(0..=Self::MAX),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Stray {
     //stray data structure, containing mutable list of up to 4 available moves, 
+    #[serde(deserialize_with = "reserve_id")]
+    pub id: u64, //unique id of this individual stray, handed out in order (0 if it has not been given one yet)
+    pub nickname: Option<String>, //name given by the owner, displayed instead of the species
+    pub original_owner: Option<String>, //name of the first trainer to capture this stray
+    pub caught_at: Option<String>, //name of the place this stray was captured
+    pub caught_on: Option<u64>, //time of capture in seconds since the unix epoch
+    pub variation: StatVariation, //individual stat variation, already included in the stats below
     pub species: String, //species of stray
     pub s_type: Type, //type of species
     pub moves: [Option<Move>; 4], //available moves
@@ -223,7 +379,13 @@ impl Default for Stray {
             spd: 35,
             cur_hp: 15,
             owner: true,
-            texture: String::from("assets/strays/palliub.png")
+            texture: String::from("assets/strays/palliub.png"),
+            id: 0,
+            nickname: None,
+            original_owner: None,
+            caught_at: None,
+            caught_on: None,
+            variation: StatVariation::default(),
         }
     }
 }
@@ -241,8 +403,10 @@ impl Stray {
             spd: 10,
             cur_hp: 20,
             owner,
-            texture: String::from("assets/strays/bitaxum.png")
+            texture: String::from("assets/strays/bitaxum.png"),
+            ..Default::default()
         }
+        .individual()
     }
 
     pub fn palliub(owner: bool) -> Self {
@@ -256,8 +420,10 @@ impl Stray {
             spd: 35,
             cur_hp: 15,
             owner,
-            texture: String::from("assets/strays/palliub.png")
+            texture: String::from("assets/strays/palliub.png"),
+            ..Default::default()
         }
+        .individual()
     }
 
     pub fn rubridum(owner: bool) -> Self {
@@ -271,8 +437,10 @@ impl Stray {
             spd: 25,
             cur_hp: 15,
             owner,
-            texture: String::from("assets/strays/rubridum.png")
+            texture: String::from("assets/strays/rubridum.png"),
+            ..Default::default()
         }
+        .individual()
     }
 
     pub fn aeternisc(owner: bool) -> Self {
@@ -286,8 +454,10 @@ impl Stray {
             spd: 10,
             cur_hp: 50,
             owner,
-            texture: String::from("assets/strays/aeternisc.png")
+            texture: String::from("assets/strays/aeternisc.png"),
+            ..Default::default()
         }
+        .individual()
     }
 
     pub fn solikigoi(owner: bool) -> Self {
@@ -301,8 +471,10 @@ impl Stray {
             spd: 15,
             cur_hp: 20,
             owner,
-            texture: String::from("assets/strays/solikigoi.png")
+            texture: String::from("assets/strays/solikigoi.png"),
+            ..Default::default()
         }
+        .individual()
     }
 
     pub fn catis(owner: bool) -> Self {
@@ -316,8 +488,10 @@ impl Stray {
             spd: 10,
             cur_hp: 25,
             owner,
-            texture: String::from("assets/strays/catis.png")
+            texture: String::from("assets/strays/catis.png"),
+            ..Default::default()
         }
+        .individual()
     }
     
     pub fn cespae(owner: bool) -> Self {
@@ -331,8 +505,10 @@ impl Stray {
             spd: 20,
             cur_hp: 15,
             owner,
-            texture: String::from("assets/strays/cespae.png")
+            texture: String::from("assets/strays/cespae.png"),
+            ..Default::default()
         }
+        .individual()
     }
 
     pub fn omikae(owner: bool) -> Self {
@@ -346,8 +522,10 @@ impl Stray {
             spd: 20,
             cur_hp: 20,
             owner,
-            texture: String::from("assets/strays/omikae.png")
+            texture: String::from("assets/strays/omikae.png"),
+            ..Default::default()
         }
+        .individual()
     }
 
     pub fn carerus(owner: bool) -> Self {
@@ -361,9 +539,90 @@ impl Stray {
             spd: 20,
             cur_hp: 20,
             owner,
-            texture: String::from("assets/strays/carerus.png")
+            texture: String::from("assets/strays/carerus.png"),
+            ..Default::default()
         }
+        .individual()
     }
-}
 
+    //constructs a stray from the name of its species, as written in map data
+    //returns None if there is no species with that name
+    pub fn from_species(species: &str, owner: bool) -> Option<Self> {
+        match species {
+            "Bitaxum" => Some(Self::bitaxum(owner)),
+            "Palliub" => Some(Self::palliub(owner)),
+            "Rubridum" => Some(Self::rubridum(owner)),
+            "Aeternisc" => Some(Self::aeternisc(owner)),
+            "Solikigoi" => Some(Self::solikigoi(owner)),
+            "Catis" => Some(Self::catis(owner)),
+            "Cespae" => Some(Self::cespae(owner)),
+            "Omikae" => Some(Self::omikae(owner)),
+            "Carerus" => Some(Self::carerus(owner)),
+            _ => None,
+        }
+    }
+
+    //gives a freshly constructed stray its own id and rolls its individual stat variation
+    fn individual(mut self) -> Self {
+        self.id = NEXT_STRAY_ID.fetch_add(1, Ordering::Relaxed);
+        self.variation = StatVariation::random();
+        self.hp += self.variation.hp;
+        self.cur_hp = self.hp;
+        self.atk += self.variation.atk;
+        self.def += self.variation.def;
+        self.spd += self.variation.spd;
+        self
+    }
+
+    //gives the stray a nickname, shown instead of its species
+    //an empty name (or the species itself) takes the nickname away
+    pub fn rename(&mut self, nickname: &str) {
+        let nickname = nickname.trim();
+        self.nickname = match nickname.is_empty() || nickname == self.species {
+            true => None,
+            false => Some(nickname.to_string()),
+        };
+    }
+
+    //returns the name shown in game, which is the nickname if the stray has one
+    pub fn name(&self) -> &str {
+        self.nickname.as_deref().unwrap_or(&self.species)
+    }
 
+    //returns the chance of catching this stray with a totem of a tier, which is higher the weaker the stray is
+    //returns None if the totem's tier is too low to catch the species at all
+    pub fn catch_chance(&self, totem_tier: u32) -> Option<f32> {
+        let required = SPECIES
+            .iter()
+            .find(|info| info.name == self.species)
+            .map_or(1, |info| info.totem_tier);
+        if totem_tier < required || totem_tier == 0 {
+            return None;
+        }
+        let rate = TOTEM_CATCH_RATES[(totem_tier as usize).min(TOTEM_CATCH_RATES.len()) - 1];
+        let missing_hp = 1.0 - self.cur_hp.max(0) as f32 / self.hp.max(1) as f32;
+        Some(rate + (1.0 - rate) * missing_hp / 2.0)
+    }
+
+    //returns how the stray acts towards the player while roaming the overworld
+    pub fn temperament(&self) -> Temperament {
+        SPECIES
+            .iter()
+            .find(|info| info.name == self.species)
+            .map_or(Temperament::Calm, |info| info.temperament)
+    }
+
+    //records the capture of this stray by a trainer
+    //takes in the name of the trainer and the name of the place the stray was captured
+    pub fn capture(&mut self, trainer: &str, location: &str) {
+        self.owner = true;
+        if self.original_owner.is_none() {
+            self.original_owner = Some(trainer.to_string());
+            self.caught_at = Some(location.to_string());
+            self.caught_on = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|time| time.as_secs());
+        }
+    }
+}
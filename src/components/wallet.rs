@@ -0,0 +1,32 @@
+/****************************************************/
+// Description: The player's wallet, holding the money spent in shops and earned from battles
+/****************************************************/
+
+#[derive(Debug, Default, Clone)]
+pub struct Wallet {
+    pub money: u32,
+}
+
+impl Wallet {
+    //returns a wallet holding an amount of money
+    pub fn new(money: u32) -> Wallet {
+        Wallet { money }
+    }
+
+    //adds money to the wallet
+    pub fn add(&mut self, amount: u32) {
+        self.money = self.money.saturating_add(amount);
+    }
+
+    //takes money out of the wallet if there is enough of it
+    //returns true if the money was spent
+    pub fn spend(&mut self, amount: u32) -> bool {
+        match self.money >= amount {
+            true => {
+                self.money -= amount;
+                true
+            }
+            false => false,
+        }
+    }
+}
@@ -2,16 +2,31 @@
 // Created by: Logan Schmalz
 // Description: Constant values needed by other game files
 /****************************************************/
+pub const PLAYER_NAME: &str = "Augo"; //name of the player character, recorded as the original owner of captured strays
+pub const STARTING_MONEY: u32 = 300; //money in the player's wallet at the start of the game
+
 pub const TILE_SIZE: i32 = 16; //pixel width and height of tiles
 
 pub const RANDOM_ENCOUNTER_CHANCE: f32 = 0.1; //chance of a random stray encounter when walking in possible stray tiles (e.g. tall grass)
+pub const ATTRACT_ENCOUNTER_CHANCE: f32 = 0.25; //chance of an encounter in possible stray tiles while the spirits' attracting energy lasts
+pub const ATTRACT_OPEN_ENCOUNTER_CHANCE: f32 = 0.05; //chance of an encounter elsewhere in an encounter zone while attracting
+pub const WILD_STRAY_SIGHT: f32 = 4.0; //distance in tiles at which roaming wild strays notice the player, and chase or run away
+pub const WILD_STRAY_STEP_DELAY: f32 = 1500.0; //time in ms between the steps of roaming wild strays that haven't noticed the player
+pub const WILD_STRAY_SPAWN_DISTANCE: f32 = 5.0; //roaming wild strays only appear at least this many tiles away from the player
+pub const CHARM_STEPS: u32 = 100; //steps a spirit charm lasts if a script doesn't say how long
+pub const TOTEM_CATCH_RATES: [f32; 3] = [0.35, 0.55, 0.8]; //chance a totem of each tier catches a stray at full health, rising as the stray gets weaker
 
 pub const WALK_SPEED: f32 = 4.0 / 1000.0; // tiles per millisecond
 pub const WALKING_TIME_PER_TILE: f32 = 1.0 / WALK_SPEED; // in ms
 pub const RUN_SPEED: f32 = 8.0 / 1000.0; // tiles per millisecond
+pub const SURF_SPEED: f32 = 6.0 / 1000.0; // tiles per millisecond, riding a water stray
 pub const RUNNING_TIME_PER_TILE: f32 = 1.0 / RUN_SPEED; // in ms
 pub const _HUMAN_WIDTH: u32 = 16; //in pixels
 pub const _HUMAN_HEIGHT: u32 = 20; //in pixels
+pub const NPC_IDLE_DELAY: f32 = 2000.0; //default time in ms between the steps of wandering npcs and the turns of npcs looking around
+pub const LEDGE_HOP_DISTANCE: f32 = 2.0; //tiles travelled when hopping down a ledge, over the ledge and onto the tile below
+pub const LEDGE_HOP_HEIGHT: f32 = 8.0; //height in pixels of the top of a ledge hop
+pub const CAMERA_PAN_SPEED: f32 = 6.0 / 1000.0; // tiles per millisecond, when a script pans the camera
 pub const ROTATION_TIME: f32 = RUNNING_TIME_PER_TILE; //time it takes to rotate player in ms
 
 pub const FADE_FRAMES: i32 = 14; //number of frames in fade animation spritesheet
@@ -7,24 +7,34 @@ use std::{clone::Clone, collections::HashMap};
 
 use hecs::{CommandBuffer, Entity, World};
 use rand::{distributions::Uniform, rngs::ThreadRng, Rng};
-use sdl2::{rect::Rect, video::WindowContext};
+use sdl2::video::WindowContext;
 use enum_map::EnumMap;
 use std::collections::VecDeque;
 
 use crate::{
-    components::{animation::HumanWalkAnimation, bag::Bag, sprite::Sprite, stray::*, *},
-    constants::{FADE_FRAMES, FADE_TIME, RANDOM_ENCOUNTER_CHANCE},
+    components::{
+        animation::HumanWalkAnimation, bag::Bag, encyclopedia::Encyclopedia, flags::Flags,
+        party::Party, sprite::Sprite, stray::*, wallet::Wallet, *,
+    },
+    constants::{
+        ATTRACT_ENCOUNTER_CHANCE, ATTRACT_OPEN_ENCOUNTER_CHANCE, FADE_FRAMES, FADE_TIME, PLAYER_NAME,
+        RANDOM_ENCOUNTER_CHANCE, STARTING_MONEY,
+    },
     font_manager::FontManager,
     gamestate::battle::BattleState,
     menu::{
         main_menu::MainMenu,
         moves_menu::MovesMenu,
+        shop_menu::ShopMenu,
         textbox::Textbox,
         Menu, MenuManager,
     },
     render::Renderer,
     resource_manager::TextureManager,
-    tilemap::TileMap,
+    tilemap::{
+        format::{EncounterZone, Warp, WarpTrigger},
+        TileMap,
+    },
     vec2::{Direction, Vec2},
 };
 
@@ -32,17 +42,24 @@ use self::{
     battle::Battle,
     event::Event,
     input::{Control, KeyState},
+    script::{CameraFocus, RunningScript},
+    spawn::{despawn_map_entities, spawn_map_entities, spawn_wild_strays},
 };
 
 pub mod battle;
 pub mod event;
+mod follower;
 mod input;
+mod script;
+mod spawn;
 mod updates;
+mod wild;
 
 pub enum Screen {
     MainMenu,
     Overworld(TileMap),
     Battle(Battle),
+    Error(String), //shown when the game cannot continue, such as when a map fails to load
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -76,6 +93,9 @@ pub struct State {
     pub collisions: HashMap<usize, Entity>,
     pub rng: ThreadRng,
     pub transition: Transition,
+    pub pending_warp: Option<(i32, i32, Option<Direction>)>, //tile and facing the player is warping to, applied once the screen is faded out
+    pub script: Option<RunningScript>, //event script being run, which locks the player's input until it is over
+    pub camera: Option<CameraFocus>,   //where a script has moved the camera, which follows the player if None
 }
 
 impl Default for State {
@@ -85,13 +105,32 @@ impl Default for State {
         let mut world = World::new();
         let mut cmd = CommandBuffer::new();
 
-        //here we create a bunch of example entities for the default world
+        //load the starting map, the player begins on its spawn tile
+        let map = TileMap::load(0);
+        let spawn = match &map {
+            Ok(map) => map.spawn.map(Vec2::from).unwrap_or(Vec2(0.0, 0.0)),
+            Err(_) => Vec2(0.0, 0.0),
+        };
+
+        //the player's starting strays all belong to the player from the start
+        let mut starters = [
+            Some(Stray::cespae(true)),
+            Some(Stray::palliub(true)),
+            None,
+            Some(Stray::catis(true)),
+        ];
+        let mut encyclopedia = Encyclopedia::new();
+        for stray in starters.iter_mut().flatten() {
+            stray.capture(PLAYER_NAME, "Flardfull");
+            encyclopedia.record_caught(&stray.species);
+        }
+
         //spawn player
         let player = world.spawn((
             //entity type
             Player,
             //position
-            Position(Vec2(14f32, 15f32)),
+            Position(spawn),
             //movement of entity
             MovingEntity::new(),
             //texture for entity
@@ -107,242 +146,48 @@ impl Default for State {
             },
             //inventory
             Bag::new(),
-        ));
-
-        //spawn door object
-        let _door = world.spawn((
-            //position
-            Position(Vec2(2f32, 0f32)),
-            //texture
-            Sprite {
-                texture: String::from("assets/tiles/tilesprites.png"),
-                src: Rect::new(96, 0, 16, 16),
-                ..Default::default()
-            },
-            //collision
-            Collision,
-        ));
-
-        //spawn dad NPC
-        let _dad = world.spawn((
-            //position
-            Position(Vec2(16f32, 16f32)),
-            //movement
-            MovingEntity {
-                moving: MovingState::Moving(Direction::Left),
-                try_moving: MovingState::Moving(Direction::Left),
-                rotation: Direction::Left,
-                ..Default::default()
-            },
-            //texture
-            Sprite::character(String::from("assets/char-sprites/dadsprite.png")),
-            //collision
-            Collision,
-            Npc {
-                //interaction dialogue
-                says: "Hi hungry, I'm dad! Nice try, little child --> you are bad!".to_string(),
-                //walking path
-                path: Some(WalkingPath {
-                    path: vec![
-                        Direction::Left,
-                        Direction::Up,
-                        Direction::Right,
-                        Direction::Down,
-                    ],
-                    index: 0,
-                }),
-            },
-            //initial animation state
-            HumanWalkAnimation {
-                rotation: Direction::Left,
-                time: (1.0, 0.0),
-                left_leg: true,
-                sprinting: false,
-            },
-        ));
-
-        //spawn shaman NPC
-        let _shaman = world.spawn((
-            //position
-            Position(Vec2(30f32, 15f32)),
-            //movement
-            MovingEntity {
-                moving: MovingState::Moving(Direction::Left),
-                try_moving: MovingState::Moving(Direction::Left),
-                rotation: Direction::Left,
-                ..Default::default()
-            },
-            //texture
-            Sprite::character(String::from("assets/char-sprites/shamansprite.png")),
-            //collision
-            Collision,
-            Npc {
-                //interaction dialogue
-                says: "You have much to learn.".to_string(),
-                //walking path
-                path: Some(WalkingPath {
-                    path: vec![
-                        Direction::Left,
-                        Direction::Left,
-                        Direction::Right,
-                        Direction::Right,
-                    ],
-                    index: 0,
-                }),
-            },
-            //initial animation state
-            HumanWalkAnimation {
-                rotation: Direction::Left,
-                time: (1.0, 0.0),
-                left_leg: true,
-                sprinting: false,
-            },
-        ));
-
-        //spawn sika NPC
-        let _sika = world.spawn((
-            //position
-            Position(Vec2(30f32, 24f32)),
-            //movement
-            MovingEntity {
-                moving: MovingState::Moving(Direction::Left),
-                try_moving: MovingState::Moving(Direction::Left),
-                rotation: Direction::Left,
-                ..Default::default()
-            },
-            //texture
-            Sprite::character(String::from("assets/char-sprites/sikasprite.png")),
-            //collision
-            Collision,
-            Npc {
-                //interaction dialogue
-                says: "You are small".to_string(),
-                //walking path
-                path: Some(WalkingPath {
-                    path: vec![
-                        Direction::Up,
-                        Direction::Up,
-                        Direction::Down,
-                        Direction::Down,
-                    ],
-                    index: 0,
-                }),
-            },
-            //initial animation state
-            HumanWalkAnimation {
-                rotation: Direction::Left,
-                time: (1.0, 0.0),
-                left_leg: true,
-                sprinting: false,
+            //money
+            Wallet::new(STARTING_MONEY),
+            //owned strays
+            Party::new(starters),
+            //species seen and caught so far
+            encyclopedia,
+            //story progress
+            Flags::new(),
+            //until a shrine is used, a lost battle takes the player back to where they started
+            Respawn {
+                map: 0,
+                position: spawn,
+                facing: Direction::Down,
             },
         ));
 
-
-        //spawn mom NPC
-        let _mom = world.spawn((
-            //position
-            Position(Vec2(16f32, 24f32)),
-            //movement
-            MovingEntity {
-                moving: MovingState::Moving(Direction::Left),
-                try_moving: MovingState::Moving(Direction::Left),
-                rotation: Direction::Left,
-                ..Default::default()
-            },
-            //texture
-            Sprite::character(String::from("assets/char-sprites/momsprite.png")),
-            //collision
-            Collision,
-            Npc {
-                //interaction dialogue
-                says: "Son, I'm not real.".to_string(),
-                //walking path
-                path: Some(WalkingPath {
-                    path: vec![
-                        Direction::Up,
-                        Direction::Up,
-                        Direction::Down,
-                        Direction::Down,
-                    ],
-                    index: 0,
-                }),
-            },
-            //initial animation state
-            HumanWalkAnimation {
-                rotation: Direction::Left,
-                time: (1.0, 0.0),
-                left_leg: true,
-                sprinting: false,
-            },
-        ));
-
-        //spawn Ilasiak NPC
-        let _ilasiak = world.spawn((
-            //position
-            Position(Vec2(22f32, 18f32)),
-            //movement
-            MovingEntity {
-                moving: MovingState::Moving(Direction::Left),
-                try_moving: MovingState::Moving(Direction::Left),
-                rotation: Direction::Left,
-                ..Default::default()
-            },
-            //texture
-            Sprite::character(String::from("assets/char-sprites/ilasiaksprite.png")),
-            //collision
-            Collision,
-            Npc {
-                //interaction dialogue
-                says: "Hey bub, move outta the way!".to_string(),
-                //walking path
-                path: Some(WalkingPath {
-                    path: vec![
-                        Direction::Up,
-                        Direction::Right,
-                        Direction::Down,
-                        Direction::Left,
-                    ],
-                    index: 0,
-                }),
-            },
-            //initial animation state
+        //spawn the lead stray following the player, which starts out of sight on the player's tile
+        world.spawn((
+            Follower { hidden: true, behind: None },
+            Position(spawn),
+            MovingEntity::new(),
+            //texture for entity, swapped for the lead stray's sprite when it is drawn
+            Sprite::default(),
             HumanWalkAnimation {
-                rotation: Direction::Left,
+                rotation: Direction::Down,
                 time: (1.0, 0.0),
                 left_leg: true,
                 sprinting: false,
             },
         ));
 
-        //spawn berry object
-        let _berry1 = world.spawn((
-            //position
-            Position(Vec2(16f32, 8f32)),
-            //texture
-            Sprite::berry(),
-            //collision
-            Collision,
-            //type of entity
-            GroundItem {
-                item: bag::Item::Berry,
-                amount: 1,
-            },
-        ));
-
-        //spawn berry object
-        let _berry2 = world.spawn((
-            //position
-            Position(Vec2(16f32, 16f32)),
-            //texture
-            Sprite::berry(),
-            //collision
-            Collision,
-            //type of entity
-            GroundItem {
-                item: bag::Item::Berry,
-                amount: 1,
-            },
-        ));
+        //spawn the npcs, items and objects of the starting map, or show what is wrong with it
+        let screen = match map {
+            Ok(map) => {
+                spawn_map_entities(&mut world, &map);
+                Screen::Overworld(map)
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                Screen::Error(e.to_string())
+            }
+        };
 
         //open menu manager
         let mut menus = MenuManager::new();
@@ -351,8 +196,8 @@ impl Default for State {
 
         //initialize all variables in State
         Self {
-            screen: Screen::Overworld(TileMap::load(0)),
-            next_screen: Screen::Overworld(TileMap::load(0)),
+            screen,
+            next_screen: Screen::MainMenu,
             //screen: Screen::Battle(TEST_BATTLE),
             input: EnumMap::default(),
             paused: true,
@@ -365,6 +210,9 @@ impl Default for State {
             collisions: HashMap::new(),
             rng: rand::thread_rng(),
             transition: Transition::None,
+            pending_warp: None,
+            script: None,
+            camera: None,
         }
     }
 }
@@ -380,10 +228,13 @@ impl State {
         texture_manager: &mut TextureManager<WindowContext>,
         font_manager: &FontManager,
         delta_time: f32,
-        map: &mut TileMap,
     ) -> Result<(), String> {
         match &mut self.screen {
             Screen::MainMenu => {}
+            Screen::Error(message) => {
+                //render the problem that stopped the game
+                renderer.render_error(font_manager, message)?;
+            }
             Screen::Overworld(map) => {
                 //render overworld if we are in the overworld
                 renderer.render_overworld(
@@ -392,6 +243,7 @@ impl State {
                     &self.world,
                     map,
                     &mut self.menus,
+                    self.camera.map(|camera| camera.pos),
                 )?;
             }
             Screen::Battle(battle) => {
@@ -437,7 +289,6 @@ impl State {
     pub fn update(
         &mut self,
         delta_time: f32,
-        _map: &mut TileMap,
         font_manager: &FontManager,
     ) -> Result<(), String> {
         //determine correct input handler
@@ -448,6 +299,9 @@ impl State {
                     if self.menus.is_open() {
                         self.update_player_moving(MovingState::Idle);
                         self.paused = self.handle_input_menus(font_manager);
+                    } else if self.script.is_some() {
+                        //the player stays put while a script runs, only its dialogue takes input
+                        self.update_player_moving(MovingState::Idle);
                     } else {
                         self.handle_input_gameplay(font_manager);
                     }
@@ -456,12 +310,17 @@ impl State {
                 }
 
                 if !self.paused {
+                    self.update_charm(delta_time, font_manager);
+                    self.update_npc_goals();
+                    self.update_npc_behaviors(delta_time);
+                    self.update_follower();
+                    self.update_wild_strays(delta_time);
                     self.update_moving_objects(delta_time);
                     self.update_collisions();
                     self.update_animations(delta_time);
                 }
             }
-            Screen::MainMenu => {}
+            Screen::MainMenu | Screen::Error(_) => {}
             Screen::Battle(_) => {
                 //handle battle input while in battle
                 if self.allow_input {
@@ -471,8 +330,10 @@ impl State {
         }
 
         if !self.paused {
-            //process game events as long as the game is not paused
+            //process game events and run the current event script as long as the game is not paused
+            //events go first, so a choice just made in dialogue decides what the script does next
             self.process_events(font_manager);
+            self.update_script(delta_time, font_manager);
         }
         Ok(())
     }
@@ -485,42 +346,124 @@ impl State {
             match event {
                 Event::PlayerMoved(pos) => {
                     //if the player moved, process all possible consequences
+                    let charm = self.step_charm(font_man);
                     if let Screen::Overworld(map) = &self.screen {
-                        if map.check_encounter(pos)
-                            //if encounter occurred
-                            && self.rng.gen::<f32>() <= RANDOM_ENCOUNTER_CHANCE
+                        //riding back onto land leaves the water stray behind
+                        if !map.properties_at(pos).water {
+                            let _ = self.world.remove_one::<Surfing>(self.player);
+                        }
+
+                        //stepping onto a warp leaves the map, so nothing else can happen here
+                        if let Some(warp) = map.warp_at(pos, WarpTrigger::Step) {
+                            self.take_warp(warp.clone(), font_man);
+                            continue;
+                        }
+
+                        //stepping into a trigger starts its script, unless it only runs once and already has
+                        if let Some(trigger) = map
+                            .trigger_at(pos)
+                            .filter(|trigger| !trigger.once || !self.has_flag(&trigger.flag()))
+                            .cloned()
                         {
-                            //set next screen to battle
-                            self.next_screen = Screen::Battle(Battle::new(
-                                [
-                                    Some(Stray::cespae(true)),
-                                    Some(Stray::palliub(true)),
-                                    None,
-                                    Some(Stray::catis(true)),
-                                ],
-                                [
-                                    Some(Stray::carerus(false)),
-                                    None,
-                                    Some(Stray::rubridum(false)),
-                                    Some(Stray::omikae(false)),
-                                ],
-                            ));
-                            //set and run transition
-                            self.transition = Transition::Transitioning {
-                                transition_type: TransitionType::Fade,
-                                time: 0.0,
-                                full: false,
-                            };
-                            //freeze character
-                            self.allow_input = false;
+                            if trigger.once {
+                                self.set_flag(&trigger.flag(), true);
+                            }
+                            self.start_script(&trigger.script);
+                            continue;
                         }
+
+                        //damaging tiles hurt every stray in the party, but never enough to make one faint
+                        if map.properties_at(pos).damaging {
+                            if let Ok(party) = self.world.query_one_mut::<&mut Party>(self.player) {
+                                for stray in party.strays.iter_mut().flatten().filter(|s| s.cur_hp > 1) {
+                                    stray.cur_hp -= 1;
+                                }
+                            }
+                        }
+
+                        //strays that are able to fight, a party with none cannot be ambushed
+                        let player_strays = self
+                            .world
+                            .query_one_mut::<&Party>(self.player)
+                            .map(|party| party.battle_strays())
+                            .unwrap_or_default();
+
+                        //the spirits' charms change the chance of an encounter: attracting makes them more common
+                        //and possible anywhere in an encounter zone, while repelling stops them altogether
+                        //strays roaming a zone in plain sight are met by touching them, never at random
+                        let hidden = |zone: &&EncounterZone| zone.roaming == 0;
+                        let zone = match (map.encounter_zone(pos).filter(hidden), charm) {
+                            (_, Some(CharmKind::Repel)) => None,
+                            (Some(zone), Some(CharmKind::Attract)) => Some((zone, ATTRACT_ENCOUNTER_CHANCE)),
+                            (Some(zone), _) => Some((zone, RANDOM_ENCOUNTER_CHANCE)),
+                            (None, Some(CharmKind::Attract)) => map
+                                .encounter_zone_around(pos)
+                                .filter(hidden)
+                                .map(|zone| (zone, ATTRACT_OPEN_ENCOUNTER_CHANCE)),
+                            (None, _) => None,
+                        };
+
+                        //the wild strays that live here, only rolled if an encounter occurred
+                        let opponents = match zone {
+                            Some((zone, chance))
+                                if player_strays.iter().any(|s| s.is_some())
+                                    && self.rng.gen::<f32>() <= chance =>
+                            {
+                                zone.roll_opponents(&mut self.rng, charm == Some(CharmKind::Rare))
+                            }
+                            _ => Default::default(),
+                        };
+
+                        self.start_battle(opponents, 0);
                     }
                 }
                 Event::TransitionFull => {
                     //after transition is done, swap screens and open necessary menus
                     std::mem::swap(&mut self.screen, &mut self.next_screen);
+                    //when arriving through a warp, swap the old map's entities for the new one's
+                    //and place the player on the destination tile
+                    //the player is placed first, so wild strays don't appear right next to them
+                    if let Some((x, y, facing)) = self.pending_warp.take() {
+                        self.arrive(x, y, facing);
+                    }
+                    //when leaving a battle, the party keeps whatever happened to its strays
+                    //and beating a trainer pays out their reward, while losing sends the player back to their shrine
+                    //new wild strays roam in to replace any the player battled
+                    let mut lost = false;
+                    if let (Screen::Overworld(map), Screen::Battle(battle)) =
+                        (&self.screen, &self.next_screen)
+                    {
+                        lost = battle.player_strays.iter().all(|s| s.is_none());
+                        if let Ok((party, wallet)) =
+                            self.world.query_one_mut::<(&mut Party, &mut Wallet)>(self.player)
+                        {
+                            party.sync_from_battle(&battle.player_strays);
+                            if battle.reward > 0 && battle.opponent_strays.iter().all(|s| s.is_none()) {
+                                wallet.add(battle.reward);
+                                self.menus.open_menu(
+                                    Textbox::new(
+                                        &format!("{} got ${} for winning!", PLAYER_NAME, battle.reward),
+                                        font_man,
+                                    )
+                                    .into(),
+                                );
+                            }
+                        }
+                        spawn_wild_strays(&mut self.world, map);
+                    }
+                    if lost {
+                        self.respawn_player(font_man);
+                    }
                     if matches!(self.screen, Screen::Battle(_)) {
                         if let Screen::Battle(battle) = &mut self.screen {
+                            //every opponent in the battle now counts as seen
+                            if let Ok(encyclopedia) =
+                                self.world.query_one_mut::<&mut Encyclopedia>(self.player)
+                            {
+                                for stray in battle.opponent_strays.iter().flatten() {
+                                    encyclopedia.record_seen(&stray.species);
+                                }
+                            }
                             battle.menus.open_menu(
                                 MovesMenu::new(
                                     battle.player_strays[battle.turn_order[0]]
@@ -541,13 +484,18 @@ impl State {
                         .world
                         .query_one_mut::<(&mut MovingEntity, &mut Npc)>(entity)
                         .unwrap();
+                    //NPCs walking to a goal pick up their path again once they reach it
+                    if npc.goal.is_some() {
+                        continue;
+                    }
+                    //move on to the next step of the NPC's path, which is taken once it is done waiting
                     if let Some(path) = &mut npc.path {
-                        //move NPC in direction defined by their path
                         path.advance();
-                        moving.rotation = path.direction();
-                        moving.try_moving = MovingState::Moving(path.direction());
                     }
+                    npc.wait = npc.step_delay;
+                    moving.try_moving = MovingState::Idle;
                 }
+                Event::DialogueChoice(index) => self.choose_script_option(index),
                 Event::BattleAttack(selection) => {
                     //process move selection in battles
                     println!("{:?}", &selection); //print the selected move
@@ -601,6 +549,43 @@ impl State {
                                 }
                             }
                         }
+                        //throw the player's totem at a wild stray, which joins the party if it is caught
+                        BattleState::SelectingCatchStray => {
+                            let totem = self.world.get::<&Bag>(self.player).ok().and_then(|bag| bag.totem());
+                            if let (Some(totem), Some(stray)) = (totem, &battle.opponent_strays[idx]) {
+                                let chance = totem.totem_tier().and_then(|tier| stray.catch_chance(tier));
+                                let message = match chance {
+                                    None => format!("{} is too strong for your {}!", stray.name(), totem),
+                                    Some(chance) if self.rng.gen::<f32>() < chance => {
+                                        let mut caught = stray.clone();
+                                        let location = match &self.next_screen {
+                                            Screen::Overworld(map) => map.name.clone(),
+                                            _ => String::from("Flardfull"),
+                                        };
+                                        caught.capture(PLAYER_NAME, &location);
+                                        let added = self
+                                            .world
+                                            .query_one_mut::<(&mut Party, &mut Encyclopedia)>(self.player)
+                                            .is_ok_and(|(party, encyclopedia)| {
+                                                let added = party.add(caught.clone());
+                                                if added {
+                                                    encyclopedia.record_caught(&caught.species);
+                                                }
+                                                added
+                                            });
+                                        match added {
+                                            true => {
+                                                battle.opponent_strays[idx] = None;
+                                                format!("{} caught {}!", PLAYER_NAME, caught.name())
+                                            }
+                                            false => format!("There is no room in your party for {}.", caught.name()),
+                                        }
+                                    }
+                                    Some(_) => format!("{} broke free of the {}!", stray.name(), totem),
+                                };
+                                battle.menus.open_menu(Textbox::new(&message, font_man).into());
+                            }
+                        }
                         _ => panic!("something bad happened"),
                     }
 
@@ -698,16 +683,14 @@ impl State {
                                 battle.menus.open_menu(
                                     Textbox::new(
                                         &("".to_owned()
-                                            + &String::from(
-                                                &battle.opponent_strays[battle.turn_order[0] - 4]
-                                                    .as_ref()
-                                                    .unwrap()
-                                                    .species,
-                                            )
+                                            + battle.opponent_strays[battle.turn_order[0] - 4]
+                                                .as_ref()
+                                                .unwrap()
+                                                .name()
                                             + " used "
                                             + &mv.name
                                             + " on "
-                                            + &p_stray.species
+                                            + p_stray.name()
                                             + "!"),
                                         font_man,
                                     )
@@ -750,6 +733,174 @@ impl State {
         }
     }
 
+    //function for the player taking a warp from the map
+    //warps whose condition doesn't hold yet stay shut, showing why if the map says so
+    fn take_warp(&mut self, warp: Warp, font_man: &FontManager) {
+        if warp.condition.as_ref().is_none_or(|condition| self.check_condition(condition)) {
+            self.warp_player(warp.target_map, warp.target_x, warp.target_y, warp.facing);
+        } else if let Some(says) = &warp.locked_says {
+            self.menus.open_menu(Textbox::new(says, font_man).into());
+        }
+    }
+
+    //function for moving the player to a tile of a map, used by warps, scripts and respawning
+    //takes in the map's id, the tile and the direction the player faces there (None keeps their direction)
+    //loads the map and fades out, and the player is moved once the screen is fully covered
+    //if the screen is already covered, such as when leaving a lost battle, the player is moved right away
+    pub fn warp_player(&mut self, map: i32, x: i32, y: i32, facing: Option<Direction>) {
+        let covered = match self.transition {
+            Transition::None => false,
+            Transition::Transitioning { full: true, .. } => true,
+            //a warp cannot start while another transition is still fading out
+            Transition::Transitioning { full: false, .. } => return,
+        };
+
+        //a map that fails to load stops the game on an error screen instead of warping
+        let screen = match TileMap::load(map) {
+            Ok(map) => Screen::Overworld(map),
+            Err(e) => {
+                eprintln!("{}", e);
+                Screen::Error(e.to_string())
+            }
+        };
+        if covered {
+            self.screen = screen;
+            self.arrive(x, y, facing);
+            return;
+        }
+
+        self.next_screen = screen;
+        self.pending_warp = Some((x, y, facing));
+        //set and run transition
+        self.transition = Transition::Transitioning {
+            transition_type: TransitionType::Fade,
+            time: 0.0,
+            full: false,
+        };
+        //freeze character
+        self.allow_input = false;
+    }
+
+    //function for placing the player on the tile they warped to, once the map they warped to is on screen
+    //the old map's entities are swapped for the new one's after the player is placed,
+    //so wild strays don't appear right next to them
+    fn arrive(&mut self, x: i32, y: i32, facing: Option<Direction>) {
+        self.place_player(x, y, facing);
+        if let Screen::Overworld(map) = &self.screen {
+            despawn_map_entities(&mut self.world);
+            spawn_map_entities(&mut self.world, map);
+        }
+    }
+
+    //function for starting a battle against a team of strays, fading over to the battle screen
+    //takes in the opponents and the money won by beating them (0 for wild strays)
+    //nothing happens if there are no opponents or the player has no strays able to fight
+    //returns true if the battle is starting
+    pub fn start_battle(&mut self, opponents: [Option<Stray>; 4], reward: u32) -> bool {
+        let player_strays = self
+            .world
+            .query_one_mut::<&Party>(self.player)
+            .map(|party| party.battle_strays())
+            .unwrap_or_default();
+        if !matches!(self.transition, Transition::None)
+            || opponents.iter().all(|s| s.is_none())
+            || player_strays.iter().all(|s| s.is_none())
+        {
+            return false;
+        }
+
+        //set next screen to battle
+        self.next_screen = Screen::Battle(Battle {
+            reward,
+            ..Battle::new(player_strays, opponents)
+        });
+        //set and run transition
+        self.transition = Transition::Transitioning {
+            transition_type: TransitionType::Fade,
+            time: 0.0,
+            full: false,
+        };
+        //freeze character
+        self.allow_input = false;
+        true
+    }
+
+    //function for sending the player back to their respawn point after losing a battle
+    //the screen is faded out, so the map is swapped right away, and the party is healed
+    fn respawn_player(&mut self, font_man: &FontManager) {
+        let Ok((map, Vec2(x, y), facing)) = self
+            .world
+            .get::<&Respawn>(self.player)
+            .map(|respawn| (respawn.map, respawn.position, respawn.facing))
+        else {
+            return;
+        };
+        self.warp_player(map, x as i32, y as i32, Some(facing));
+
+        //whatever script started the battle is over
+        self.script = None;
+        if let Ok(party) = self.world.query_one_mut::<&mut Party>(self.player) {
+            party.heal();
+        }
+        self.menus.open_menu(
+            Textbox::new(
+                &format!("{} hurried back to safety, and the strays were nursed back to health.", PLAYER_NAME),
+                font_man,
+            )
+            .into(),
+        );
+    }
+
+    //function for upgrading the player's totem to the next tier, using up the materials it takes
+    //returns what happened, to be shown to the player
+    pub fn upgrade_totem(&mut self) -> String {
+        match self.world.query_one_mut::<&mut Bag>(self.player) {
+            Ok(bag) => match bag.upgrade_totem() {
+                Ok((old, new)) => format!("The spirits upgraded your {} into a {}!", old, new),
+                Err(e) => e,
+            },
+            Err(_) => String::new(),
+        }
+    }
+
+    //function for placing the player on a tile of the map they warped to
+    //the player stops moving and faces the direction given, if any
+    fn place_player(&mut self, x: i32, y: i32, facing: Option<Direction>) {
+        if let Ok((position, moving, animation)) = self
+            .world
+            .query_one_mut::<(&mut Position, &mut MovingEntity, &mut HumanWalkAnimation)>(
+                self.player,
+            )
+        {
+            position.0 = Vec2::from((x, y));
+            moving.moving = MovingState::Idle;
+            moving.try_moving = MovingState::Idle;
+            moving.forced = None;
+            moving.hop = None;
+            if let Some(facing) = facing {
+                moving.rotation = facing;
+                animation.rotation = facing;
+            }
+        }
+        //warps always lead onto land
+        let _ = self.world.remove_one::<Surfing>(self.player);
+        //collisions belong to the old map and are rebuilt on the next update
+        self.collisions.clear();
+        //the camera goes back to the player on the new map
+        self.camera = None;
+    }
+
+    //sends an NPC walking somewhere, or stops it with None
+    //takes in the NPC and its new goal, such as a tile or an entity to follow
+    pub fn set_npc_goal(&mut self, npc: Entity, goal: Option<MoveGoal>) {
+        if let Ok((moving, npc)) = self.world.query_one_mut::<(&mut MovingEntity, &mut Npc)>(npc) {
+            npc.goal = goal;
+            if goal.is_none() {
+                moving.try_moving = MovingState::Idle;
+            }
+        }
+    }
+
     //function for checking for collisions between entities
     //takes in a position and returns a bool
     pub fn check_collision(&self, position: &Vec2) -> bool {
@@ -758,6 +909,14 @@ impl State {
             || self.collisions.contains_key(&position.to_usize(map.size_x))
     }
 
+    //function for checking for collisions for an entity surfing on a water stray
+    //takes in a position and returns a bool
+    pub fn check_surf_collision(&self, position: &Vec2) -> bool {
+        let Screen::Overworld(map) = &self.screen else { panic!(); };
+        map.check_surf_collision(*position)
+            || self.collisions.contains_key(&position.to_usize(map.size_x))
+    }
+
     //function for player interaction with entities
     //takes in the font manager
     pub fn try_player_interaction(&mut self, font_man: &FontManager) {
@@ -781,6 +940,12 @@ impl State {
             Direction::Down => Vec2(x, y + 1.0),
         };
 
+        //a door or other warp in front of the player takes them to another map
+        if let Some(warp) = map.warp_at(temp_pos, WarpTrigger::Interact) {
+            self.take_warp(warp.clone(), font_man);
+            return;
+        }
+
         //check for an entity based on player position in the collisions map
         let interact_entity = self
             .collisions
@@ -788,17 +953,64 @@ impl State {
 
         //if it matches, run the list of interactions it comes with
         if let Some((_, &entity)) = interact_entity {
-            let npc = self.world.query_one_mut::<&Npc>(entity);
-            if let Ok(Npc { says, .. }) = npc {
-                self.menus.open_menu(Textbox::new(says, font_man).into());
+            if let Ok(npc) = self.world.query_one_mut::<&mut Npc>(entity) {
+                //the NPC stops to face the player while they talk, running its script if it has one
+                npc.talking = true;
+                match npc.script.clone() {
+                    Some(script) => self.start_script(&script),
+                    None => {
+                        self.menus.open_menu(
+                            Textbox::with_pages(npc.name.clone(), std::slice::from_ref(&npc.says), font_man).into(),
+                        );
+                        //shopkeepers open their shop once they are done talking
+                        let stock = self.world.get::<&Shop>(entity).map(|shop| shop.stock.clone());
+                        if let Ok(stock) = stock {
+                            self.menus.open_menu(
+                                ShopMenu::new(self.player, stock, &self.world, font_man).into(),
+                            );
+                        }
+                    }
+                }
+                return;
+            }
+
+            //shrines restore the party and become where the player returns to after losing a battle
+            if self.world.satisfies::<&Shrine>(entity).unwrap_or(false) {
+                let map = map.id;
+                if let Ok((party, respawn, moving)) = self
+                    .world
+                    .query_one_mut::<(&mut Party, &mut Respawn, &MovingEntity)>(self.player)
+                {
+                    party.heal();
+                    *respawn = Respawn {
+                        map,
+                        position: Vec2(x, y),
+                        facing: moving.rotation,
+                    };
+                }
+                let mut pages = vec![String::from(
+                    "The spirits of the shrine restored your strays. You will return here if they are defeated.",
+                )];
+                //the spirits also upgrade the player's totem, if it can be and they have the materials for it
+                let upgradable = self
+                    .world
+                    .get::<&Bag>(self.player)
+                    .is_ok_and(|bag| bag.totem().is_some_and(|totem| totem.totem_upgrade().is_some()));
+                if upgradable {
+                    pages.push(self.upgrade_totem());
+                }
+                self.menus
+                    .open_menu(Textbox::with_pages(None, &pages, font_man).into());
                 return;
             }
 
-            if let Ok(&GroundItem { item, amount }) =
+            if let Ok(GroundItem { item, amount, flag }) =
                 self.world.query_one_mut::<&GroundItem>(entity)
             {
-                if let Ok(bag) = self.world.query_one_mut::<&mut Bag>(self.player) {
+                let (item, amount, flag) = (*item, *amount, flag.clone());
+                if let Ok((bag, flags)) = self.world.query_one_mut::<(&mut Bag, &mut Flags)>(self.player) {
                     if bag.add_item(item, amount) {
+                        flags.set(&flag);
                         self.menus.open_menu(
                             Textbox::new(
                                 &format!("You picked up {} (x{}).", item, amount),
@@ -810,6 +1022,49 @@ impl State {
                     }
                 };
             }
+            return;
+        }
+
+        //the lead stray following the player can be talked to as well
+        if let Some(says) = self.follower_says(temp_pos) {
+            self.menus.open_menu(Textbox::new(&says, font_man).into());
+            return;
+        }
+
+        //facing water with a water stray in the party rides the stray onto the water
+        let Screen::Overworld(map) = &self.screen else { panic!(); };
+        let surfing = self.world.satisfies::<&Surfing>(self.player).unwrap_or(false);
+        if surfing || !map.properties_at(temp_pos).water || map.check_surf_collision(temp_pos) {
+            return;
+        }
+        let Some(stray) = self
+            .world
+            .query_one_mut::<&Party>(self.player)
+            .ok()
+            .and_then(|party| party.surfer().cloned())
+        else {
+            return;
+        };
+
+        self.world
+            .insert_one(
+                self.player,
+                Surfing {
+                    stray: stray.name().to_string(),
+                    texture: stray.texture.clone(),
+                },
+            )
+            .unwrap();
+        //the stray carries the player onto the water
+        if let Ok(moving) = self.world.query_one_mut::<&mut MovingEntity>(self.player) {
+            moving.forced = Some(moving.rotation);
         }
+        self.menus.open_menu(
+            Textbox::new(
+                &format!("{} rode {} onto the water.", PLAYER_NAME, stray.name()),
+                font_man,
+            )
+            .into(),
+        );
     }
 }
@@ -16,6 +16,7 @@ pub enum BattleState {
     SelectingMove,
     SelectingOpponentStray,
     SelectingFriendlyStray,
+    SelectingCatchStray, //picking the wild stray to throw the player's totem at
 }
 
 #[derive(Default)]
@@ -27,6 +28,7 @@ pub struct Battle {
     pub battle_state: BattleState,
     pub turn_order: VecDeque<usize>,
     pub menus: MenuManager,
+    pub reward: u32, //money the player gets for winning, given by trainers, whose strays can't be caught
 }
 
 impl Battle {
@@ -68,6 +70,7 @@ impl Battle {
             turn_order,
             selected_stray: None,
             menus: MenuManager::new(),
+            reward: 0,
         }
     }
 
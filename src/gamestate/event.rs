@@ -13,4 +13,5 @@ pub enum Event {
     BattleAttack(Move),
     AttackStray(usize),
     TransitionFull,
+    DialogueChoice(usize), //index of the option picked in a script's choice menu
 }
@@ -0,0 +1,113 @@
+/****************************************************/
+// Description: The lead stray of the party following the player around the overworld,
+// walking onto each tile the player leaves, and what it does when talked to
+/****************************************************/
+use rand::seq::SliceRandom;
+
+use crate::{
+    components::{
+        animation::HumanWalkAnimation, party::Party, Follower, MovingEntity, MovingState, Position,
+        Surfing,
+    },
+    vec2::{compute_direction, Direction, Vec2},
+};
+
+use super::{Screen, State};
+
+impl State {
+    //walks the lead stray one tile behind the player, onto the tile the player is leaving, and keeps it there
+    //while the player stands still, so it can be talked to
+    //the stray waits out of sight on the player's tile while it can't follow, and catches up at once if it falls behind
+    pub fn update_follower(&mut self) {
+        let Screen::Overworld(map) = &self.screen else { return };
+
+        let Ok(mut query) = self
+            .world
+            .query_one::<(&Position, &MovingEntity, &Party, Option<&Surfing>)>(self.player)
+        else {
+            return;
+        };
+        let Some((&Position(player), player_moving, party, surfing)) = query.get() else { return };
+        let (moving_state, sprinting) = (player_moving.moving, player_moving.sprinting);
+        let hidden = map.indoors || surfing.is_some() || party.lead().is_none();
+        drop(query);
+
+        let Vec2(x, y) = player;
+        let tile = player.round_to_tile();
+
+        for (_, (follower, position, moving, animation)) in self.world.query_mut::<(
+            &mut Follower,
+            &mut Position,
+            &mut MovingEntity,
+            &mut HumanWalkAnimation,
+        )>() {
+            //the tile the player is walking away from, or the last one they left while standing still
+            //the player's own tile is only used while the stray is out of sight, or when there is no tile next
+            //to the player to stand on yet, such as right after a warp
+            let behind = match moving_state {
+                MovingState::Moving(Direction::Left) => Vec2(x.ceil(), y),
+                MovingState::Moving(Direction::Right) => Vec2(x.floor(), y),
+                MovingState::Moving(Direction::Up) => Vec2(x, y.ceil()),
+                MovingState::Moving(Direction::Down) => Vec2(x, y.floor()),
+                MovingState::Idle | MovingState::CenterTile => match follower.behind {
+                    Some(last) if !hidden && last.dist(tile) == 1.0 => last,
+                    _ => tile,
+                },
+            };
+            if let MovingState::Moving(_) = moving_state {
+                follower.behind = Some(behind);
+            }
+
+            //the stray only picks where to go next once it is standing on a tile
+            if !matches!(moving.moving, MovingState::Idle | MovingState::CenterTile) {
+                continue;
+            }
+
+            let distance = position.0.dist(behind);
+            if hidden || distance > 1.0 {
+                position.0 = behind;
+                moving.moving = MovingState::Idle;
+                moving.try_moving = MovingState::Idle;
+            } else if distance == 0.0 {
+                moving.try_moving = MovingState::Idle;
+            } else {
+                //the stray turns without stopping, so it keeps up with the player
+                let direction = compute_direction(position.0, behind);
+                moving.rotation = direction;
+                animation.rotation = direction;
+                moving.try_moving = MovingState::Moving(direction);
+                moving.try_sprinting = sprinting;
+            }
+
+            //standing on the player's tile, the stray is out of sight until the player walks off
+            follower.hidden = hidden || position.0 == player;
+        }
+    }
+
+    //checks for the following stray on a tile the player is facing
+    //returns what the player sees it doing, or None if it isn't there
+    pub fn follower_says(&mut self, pos: Vec2) -> Option<String> {
+        let here = self
+            .world
+            .query_mut::<(&Follower, &Position)>()
+            .into_iter()
+            .any(|(_, (follower, position))| !follower.hidden && position.0 == pos);
+        if !here {
+            return None;
+        }
+
+        let party = self.world.get::<&Party>(self.player).ok()?;
+        let stray = party.lead()?;
+        let name = stray.name();
+        if stray.cur_hp * 2 < stray.hp {
+            return Some(format!("{} looks tired. A rest at a shrine would do it good.", name));
+        }
+        let lines = [
+            format!("{} is happily following you.", name),
+            format!("{} sniffs the air curiously.", name),
+            format!("{} looks up at you, full of energy.", name),
+            format!("{} is keeping a close eye on the tall grass.", name),
+        ];
+        lines.choose(&mut self.rng).cloned()
+    }
+}
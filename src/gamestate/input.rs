@@ -10,10 +10,10 @@ use sdl2::{
 };
 
 use crate::{
-    components::MovingState,
+    components::{bag::Bag, MovingState},
     font_manager::FontManager,
     gamestate::battle::BattleState,
-    menu::{menu_events::MenuInput, pause_menu::PauseMenu},
+    menu::{self, menu_events::MenuInput, pause_menu::PauseMenu},
     render::Renderer,
     vec2::Direction,
 };
@@ -255,12 +255,31 @@ impl State {
             if close {
                 battle.menus.close_menu();
             }
+
+            //while picking a move, the player can throw their totem at a wild stray instead
+            let has_totem = self
+                .world
+                .get::<&Bag>(self.player)
+                .is_ok_and(|bag| bag.totem().is_some());
+            if self.input[Menu] == Pressed
+                && has_totem
+                && battle.reward == 0
+                && matches!(battle.battle_state, BattleState::SelectingMove)
+                && matches!(battle.menus.menus.last(), Some(menu::Menu::MovesMenu(_)))
+            {
+                battle.battle_state = BattleState::SelectingCatchStray;
+                battle.selected_stray = battle.get_left_opponent_stray(Some(0));
+                battle.menus.close_menu(); //close move menu
+                return;
+            }
         }
 
         //special handling for if the battle is in a state where the player is selecting a stray
         if matches!(
             battle.battle_state,
-            BattleState::SelectingOpponentStray | BattleState::SelectingFriendlyStray
+            BattleState::SelectingOpponentStray
+                | BattleState::SelectingFriendlyStray
+                | BattleState::SelectingCatchStray
         ) {
             if self.input[Interact1] == Pressed {
                 if let Some(index) = battle.selected_stray {
@@ -270,7 +289,7 @@ impl State {
                 }
             } else if self.input[Left] == Pressed {
                 match battle.battle_state {
-                    BattleState::SelectingOpponentStray => {
+                    BattleState::SelectingOpponentStray | BattleState::SelectingCatchStray => {
                         battle.selected_stray =
                             battle.get_left_opponent_stray(battle.selected_stray)
                     }
@@ -281,7 +300,7 @@ impl State {
                 }
             } else if self.input[Right] == Pressed {
                 match battle.battle_state {
-                    BattleState::SelectingOpponentStray => {
+                    BattleState::SelectingOpponentStray | BattleState::SelectingCatchStray => {
                         battle.selected_stray =
                             battle.get_right_opponent_stray(battle.selected_stray)
                     }
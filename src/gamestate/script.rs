@@ -0,0 +1,375 @@
+/****************************************************/
+// Description: Running event scripts, stepping through their steps one at a time
+// while the player's input is locked, and panning the camera for them
+/****************************************************/
+use std::collections::VecDeque;
+
+use hecs::Entity;
+
+use crate::{
+    components::{
+        animation::HumanWalkAnimation, bag::Bag, flags::Flags, party::Party, stray::Stray, *,
+    },
+    constants::{CAMERA_PAN_SPEED, CHARM_STEPS},
+    font_manager::FontManager,
+    menu::{choice_menu::ChoiceMenu, textbox::Textbox},
+    script::{Condition, Script, ScriptStep},
+    vec2::Vec2,
+};
+
+use super::{Screen, State, Transition};
+
+//a script being run, with the steps it has left
+pub struct RunningScript {
+    pub name: String,
+    steps: VecDeque<ScriptStep>,
+    waiting: ScriptWait,
+    choices: Vec<Vec<ScriptStep>>, //steps of each option offered by the open choice menu
+}
+
+//what the current step of a script is waiting for before the next one starts
+#[derive(Debug, Clone, Copy)]
+enum ScriptWait {
+    Nothing,
+    Time(f32),               //ms left to wait
+    Dialogue,                //the textbox to be closed
+    Npc(Entity),             //the npc to reach its goal
+    Camera,                  //the camera to finish panning
+    Warp,                    //the screen to fade back in on the new map
+    Battle { started: bool }, //the battle to start and then end
+}
+
+//where the camera looks while a script has moved it away from the player
+#[derive(Debug, Clone, Copy)]
+pub struct CameraFocus {
+    pub pos: Vec2,       //tile the camera is centred on
+    pub target: Vec2,    //tile the camera is panning to
+    pub returning: bool, //panning back to the player, who it follows again once it gets there
+}
+
+impl State {
+    //starts running an event script, unless one is already running
+    //takes in the name of the script, a broken or missing script is reported and skipped
+    pub fn start_script(&mut self, name: &str) {
+        if self.script.is_some() {
+            return;
+        }
+        match Script::load(name) {
+            Ok(script) => {
+                self.script = Some(RunningScript {
+                    name: name.to_string(),
+                    steps: script.steps.into(),
+                    waiting: ScriptWait::Nothing,
+                    choices: vec![],
+                })
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+
+    //runs the steps of the current script until one has to wait, or the script is over
+    //takes in the time since the last update and the font manager
+    pub fn update_script(&mut self, delta_time: f32, font_man: &FontManager) {
+        self.update_camera_pan(delta_time);
+
+        while let Some(script) = &self.script {
+            if let Some(waiting) = self.poll_script_wait(script.waiting, delta_time) {
+                if let Some(script) = &mut self.script {
+                    script.waiting = waiting;
+                }
+                return;
+            }
+
+            let Some(step) = self.script.as_mut().and_then(|script| script.steps.pop_front()) else {
+                self.script = None;
+                return;
+            };
+            let waiting = self.run_script_step(step, font_man);
+            if let Some(script) = &mut self.script {
+                script.waiting = waiting;
+            }
+        }
+    }
+
+    //checks if a script is still waiting
+    //returns what it is still waiting for, or None once it can carry on
+    fn poll_script_wait(&mut self, waiting: ScriptWait, delta_time: f32) -> Option<ScriptWait> {
+        let done = match waiting {
+            ScriptWait::Nothing => true,
+            ScriptWait::Time(time) => {
+                if time > delta_time {
+                    return Some(ScriptWait::Time(time - delta_time));
+                }
+                true
+            }
+            ScriptWait::Dialogue => !self.menus.is_open(),
+            //an npc that has despawned (such as after a warp) isn't walking anywhere anymore
+            ScriptWait::Npc(entity) => self
+                .world
+                .get::<&Npc>(entity)
+                .ok()
+                .is_none_or(|npc| npc.goal.is_none()),
+            ScriptWait::Camera => self.camera.is_none_or(|camera| camera.pos == camera.target),
+            ScriptWait::Warp => {
+                matches!(self.transition, Transition::None) && self.pending_warp.is_none()
+            }
+            ScriptWait::Battle { started } => {
+                if matches!(self.screen, Screen::Battle(_)) {
+                    return Some(ScriptWait::Battle { started: true });
+                }
+                //then waits for anything said once it is over, such as the reward for winning
+                started && matches!(self.transition, Transition::None) && !self.menus.is_open()
+            }
+        };
+        match done {
+            true => None,
+            false => Some(waiting),
+        }
+    }
+
+    //starts one step of a script
+    //returns what the script has to wait for before the next step
+    fn run_script_step(&mut self, step: ScriptStep, font_man: &FontManager) -> ScriptWait {
+        match step {
+            ScriptStep::Say { speaker, text } => {
+                self.menus
+                    .open_menu(Textbox::with_pages(speaker, &text.pages(), font_man).into());
+                ScriptWait::Dialogue
+            }
+            ScriptStep::Choice { speaker, text, options } => {
+                let (names, choices): (Vec<String>, Vec<Vec<ScriptStep>>) = options
+                    .into_iter()
+                    .filter(|option| option.condition.as_ref().is_none_or(|c| self.check_condition(c)))
+                    .map(|option| (option.text, option.steps))
+                    .unzip();
+                if names.is_empty() {
+                    return ScriptWait::Nothing;
+                }
+                if let Some(script) = &mut self.script {
+                    script.choices = choices;
+                }
+                self.menus
+                    .open_menu(ChoiceMenu::new(speaker, &text, names, font_man).into());
+                ScriptWait::Dialogue
+            }
+            ScriptStep::If { condition, then, otherwise } => {
+                let steps = match self.check_condition(&condition) {
+                    true => then,
+                    false => otherwise,
+                };
+                self.insert_script_steps(steps);
+                ScriptWait::Nothing
+            }
+            ScriptStep::MoveNpc { npc, x, y, wait } => {
+                let Some(entity) = self.find_npc(&npc) else {
+                    eprintln!("script {}: no npc \"{}\" on this map", self.script_name(), npc);
+                    return ScriptWait::Nothing;
+                };
+                self.set_npc_goal(entity, Some(MoveGoal::Tile(Vec2::from((x, y)))));
+                match wait {
+                    true => ScriptWait::Npc(entity),
+                    false => ScriptWait::Nothing,
+                }
+            }
+            ScriptStep::FaceNpc { npc, facing } => {
+                let Some(entity) = self.find_npc(&npc) else {
+                    eprintln!("script {}: no npc \"{}\" on this map", self.script_name(), npc);
+                    return ScriptWait::Nothing;
+                };
+                if let Ok((moving, animation)) = self
+                    .world
+                    .query_one_mut::<(&mut MovingEntity, &mut HumanWalkAnimation)>(entity)
+                {
+                    moving.rotation = facing;
+                    animation.rotation = facing;
+                }
+                ScriptWait::Nothing
+            }
+            ScriptStep::Camera { x, y } => {
+                let pos = self.camera.map_or_else(|| self.player_position(), |camera| camera.pos);
+                self.camera = Some(CameraFocus {
+                    pos,
+                    target: Vec2::from((x, y)),
+                    returning: false,
+                });
+                ScriptWait::Camera
+            }
+            ScriptStep::CameraReset => {
+                if let Some(camera) = &mut self.camera {
+                    camera.returning = true;
+                }
+                ScriptWait::Camera
+            }
+            ScriptStep::Wait { ms } => ScriptWait::Time(ms),
+            ScriptStep::GiveItem { item, amount } => {
+                if let Ok(bag) = self.world.query_one_mut::<&mut Bag>(self.player) {
+                    bag.add_item(item, amount);
+                }
+                ScriptWait::Nothing
+            }
+            ScriptStep::TakeItem { item, amount } => {
+                if let Ok(bag) = self.world.query_one_mut::<&mut Bag>(self.player) {
+                    bag.remove_item(item, amount);
+                }
+                ScriptWait::Nothing
+            }
+            ScriptStep::Heal => {
+                if let Ok(party) = self.world.query_one_mut::<&mut Party>(self.player) {
+                    party.heal();
+                }
+                ScriptWait::Nothing
+            }
+            ScriptStep::Nickname { species, name } => {
+                if let Ok(party) = self.world.query_one_mut::<&mut Party>(self.player) {
+                    if let Some(stray) = party.strays.iter_mut().flatten().find(|stray| stray.species == species) {
+                        stray.rename(&name);
+                    }
+                }
+                ScriptWait::Nothing
+            }
+            ScriptStep::UpgradeTotem => {
+                let message = self.upgrade_totem();
+                self.menus.open_menu(Textbox::new(&message, font_man).into());
+                ScriptWait::Dialogue
+            }
+            ScriptStep::Charm { charm, steps, ms } => {
+                let steps = match (steps, ms) {
+                    (None, None) => Some(CHARM_STEPS),
+                    (steps, _) => steps,
+                };
+                self.world
+                    .insert_one(self.player, Charm { kind: charm, steps, time: ms })
+                    .unwrap();
+                ScriptWait::Nothing
+            }
+            ScriptStep::Battle { strays, reward } => {
+                let mut opponents = [None, None, None, None];
+                for (opponent, species) in opponents.iter_mut().zip(strays.iter()) {
+                    *opponent = Stray::from_species(species, false);
+                    if opponent.is_none() {
+                        eprintln!("script {}: unknown species \"{}\"", self.script_name(), species);
+                    }
+                }
+                match self.start_battle(opponents, reward) {
+                    true => ScriptWait::Battle { started: false },
+                    false => ScriptWait::Nothing,
+                }
+            }
+            ScriptStep::Warp { map, x, y, facing } => {
+                self.warp_player(map, x, y, facing);
+                ScriptWait::Warp
+            }
+            ScriptStep::SetFlag { flag } => {
+                self.set_flag(&flag, true);
+                ScriptWait::Nothing
+            }
+            ScriptStep::ClearFlag { flag } => {
+                self.set_flag(&flag, false);
+                ScriptWait::Nothing
+            }
+            ScriptStep::AddCounter { counter, amount } => {
+                if let Ok(flags) = self.world.query_one_mut::<&mut Flags>(self.player) {
+                    flags.add_counter(&counter, amount);
+                }
+                ScriptWait::Nothing
+            }
+            ScriptStep::SetCounter { counter, value } => {
+                if let Ok(flags) = self.world.query_one_mut::<&mut Flags>(self.player) {
+                    flags.set_counter(&counter, value);
+                }
+                ScriptWait::Nothing
+            }
+        }
+    }
+
+    //runs the steps of the option picked in a script's choice menu next
+    //takes in the index of the option, out of the ones that were offered
+    pub fn choose_script_option(&mut self, index: usize) {
+        let Some(script) = &mut self.script else { return };
+        let mut choices = std::mem::take(&mut script.choices);
+        if index < choices.len() {
+            self.insert_script_steps(choices.swap_remove(index));
+        }
+    }
+
+    //puts steps at the front of the running script, so they run before the rest of it
+    fn insert_script_steps(&mut self, steps: Vec<ScriptStep>) {
+        if let Some(script) = &mut self.script {
+            for step in steps.into_iter().rev() {
+                script.steps.push_front(step);
+            }
+        }
+    }
+
+    //checks a condition against the player's story flags and bag
+    pub fn check_condition(&self, condition: &Condition) -> bool {
+        match self.world.query_one::<(&Flags, &Bag)>(self.player) {
+            Ok(mut query) => query
+                .get()
+                .is_some_and(|(flags, bag)| condition.check(flags, bag)),
+            Err(_) => false,
+        }
+    }
+
+    //moves the camera towards where the script wants it, following the player again once it has panned back
+    //takes in the time since the last update
+    fn update_camera_pan(&mut self, delta_time: f32) {
+        let player = self.player_position();
+        let Some(camera) = &mut self.camera else { return };
+        if camera.returning {
+            camera.target = player;
+        }
+
+        let distance = camera.pos.dist(camera.target);
+        let step = CAMERA_PAN_SPEED * delta_time;
+        camera.pos = match distance <= step {
+            true => camera.target,
+            false => camera.pos + Vec2(
+                (camera.target.0 - camera.pos.0) * step / distance,
+                (camera.target.1 - camera.pos.1) * step / distance,
+            ),
+        };
+
+        if camera.returning && camera.pos == camera.target {
+            self.camera = None;
+        }
+    }
+
+    //checks if one of the player's story flags is set
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.world
+            .get::<&Flags>(self.player)
+            .is_ok_and(|flags| flags.is_set(flag))
+    }
+
+    //sets or clears one of the player's story flags
+    pub fn set_flag(&mut self, flag: &str, set: bool) {
+        if let Ok(flags) = self.world.query_one_mut::<&mut Flags>(self.player) {
+            match set {
+                true => flags.set(flag),
+                false => flags.clear(flag),
+            }
+        }
+    }
+
+    //finds an npc on the current map by the id given to it in the map data
+    fn find_npc(&self, id: &str) -> Option<Entity> {
+        self.world
+            .query::<&Npc>()
+            .iter()
+            .find(|(_, npc)| npc.id.as_deref() == Some(id))
+            .map(|(entity, _)| entity)
+    }
+
+    //returns the tile the player is on
+    fn player_position(&self) -> Vec2 {
+        self.world
+            .get::<&Position>(self.player)
+            .map_or(Vec2(0.0, 0.0), |position| position.0)
+    }
+
+    //returns the name of the running script, for reporting problems with it
+    fn script_name(&self) -> &str {
+        self.script.as_ref().map_or("", |script| &script.name)
+    }
+}
@@ -0,0 +1,268 @@
+/****************************************************/
+// Description: Spawning the entities declared in map data into the world, and removing them when leaving the map
+/****************************************************/
+use hecs::World;
+use rand::seq::SliceRandom;
+use sdl2::rect::Rect;
+
+use crate::{
+    components::{animation::HumanWalkAnimation, bag::Bag, flags::Flags, sprite::Sprite, *},
+    constants::{NPC_IDLE_DELAY, WILD_STRAY_SPAWN_DISTANCE, WILD_STRAY_STEP_DELAY},
+    tilemap::{format::{EntitySpawn, NpcSpawn}, TileMap},
+    vec2::{Direction, Vec2},
+};
+
+//function for spawning every entity declared by a map, along with the wild strays roaming its encounter zones
+//takes in the world and the map being entered
+//entities whose spawn condition doesn't hold for the player's progress, and items already picked up, are left out
+pub fn spawn_map_entities(world: &mut World, map: &TileMap) {
+    let (flags, bag) = world
+        .query_mut::<(&Player, &Flags, &Bag)>()
+        .into_iter()
+        .next()
+        .map(|(_, (_, flags, bag))| (flags.clone(), bag.clone()))
+        .unwrap_or_default();
+
+    for spawn in &map.entities {
+        match spawn.clone() {
+            EntitySpawn::Npc(npc) => {
+                let NpcSpawn {
+                    x,
+                    y,
+                    sprite,
+                    name,
+                    says,
+                    id,
+                    script,
+                    condition,
+                    path,
+                    path_mode,
+                    facing,
+                    wander,
+                    look_around,
+                    step_delay,
+                    shop,
+                } = *npc;
+                if condition.is_some_and(|condition| !condition.check(&flags, &bag)) {
+                    continue;
+                }
+
+                //npcs with a path start walking along it, others stand facing their direction
+                let rotation = path.first().copied().or(facing).unwrap_or(Direction::Down);
+                let moving = match path.is_empty() {
+                    true => MovingState::Idle,
+                    false => MovingState::Moving(rotation),
+                };
+                let idle = match (wander, look_around) {
+                    (Some(radius), _) => IdleBehavior::Wander {
+                        home: Vec2::from((x, y)),
+                        radius,
+                    },
+                    (None, true) => IdleBehavior::LookAround,
+                    (None, false) => IdleBehavior::Stand,
+                };
+                let step_delay = step_delay.unwrap_or(match path.is_empty() {
+                    true => NPC_IDLE_DELAY,
+                    false => 0.0,
+                });
+                let path = match path.is_empty() {
+                    true => None,
+                    false => Some(WalkingPath::new(path, path_mode)),
+                };
+
+                let npc = world.spawn((
+                    MapEntity,
+                    //position
+                    Position(Vec2::from((x, y))),
+                    //movement
+                    MovingEntity {
+                        moving,
+                        try_moving: moving,
+                        rotation,
+                        ..Default::default()
+                    },
+                    //texture
+                    Sprite::character(sprite),
+                    //collision
+                    Collision,
+                    //interaction dialogue or script and walking path
+                    Npc {
+                        id,
+                        name,
+                        script,
+                        idle,
+                        step_delay,
+                        ..Npc::new(says, path)
+                    },
+                    //initial animation state
+                    HumanWalkAnimation {
+                        rotation,
+                        time: (1.0, 0.0),
+                        left_leg: true,
+                        sprinting: false,
+                    },
+                ));
+                //shopkeepers sell their items at the price given, or the item's usual price
+                //totems are never for sale, and neither are items without a price
+                if !shop.is_empty() {
+                    let stock = shop
+                        .into_iter()
+                        .filter_map(|entry| match (entry.item.totem_tier(), entry.price.or(entry.item.price())) {
+                            (None, Some(price)) => Some((entry.item, price)),
+                            _ => {
+                                eprintln!("map {}: {} can't be sold in shops", map.id, entry.item);
+                                None
+                            }
+                        })
+                        .collect();
+                    world.insert_one(npc, Shop { stock }).unwrap();
+                }
+            }
+            EntitySpawn::Item { x, y, item, amount } => {
+                let flag = format!("item:{}:{}:{}", map.id, x, y);
+                if flags.is_set(&flag) {
+                    continue;
+                }
+
+                world.spawn((
+                    MapEntity,
+                    //position
+                    Position(Vec2::from((x, y))),
+                    //texture
+                    Sprite::item(item),
+                    //collision
+                    Collision,
+                    //type of entity
+                    GroundItem { item, amount, flag },
+                ));
+            }
+            EntitySpawn::Sign { x, y, says } => {
+                //signs are read like npcs that never move, their graphic is part of the map tiles
+                world.spawn((
+                    MapEntity,
+                    //position
+                    Position(Vec2::from((x, y))),
+                    //collision
+                    Collision,
+                    Npc::new(says, None),
+                ));
+            }
+            EntitySpawn::Shrine { x, y } => {
+                world.spawn((
+                    MapEntity,
+                    //position
+                    Position(Vec2::from((x, y))),
+                    //texture
+                    Sprite::shrine(),
+                    //collision
+                    Collision,
+                    //type of entity
+                    Shrine,
+                ));
+            }
+            EntitySpawn::Door { x, y } => {
+                world.spawn((
+                    MapEntity,
+                    //position
+                    Position(Vec2::from((x, y))),
+                    //texture
+                    Sprite {
+                        texture: String::from("assets/tiles/tilesprites.png"),
+                        src: Rect::new(96, 0, 16, 16),
+                        ..Default::default()
+                    },
+                    //collision
+                    Collision,
+                ));
+            }
+        }
+    }
+
+    spawn_wild_strays(world, map);
+}
+
+//function for filling the map's encounter zones with the wild strays that roam them in plain sight
+//takes in the world and the map, topping up zones that are missing strays, such as after one was battled
+//strays only appear on free tiles of their zone's kind, away from the player so they can't be run into straight away
+pub fn spawn_wild_strays(world: &mut World, map: &TileMap) {
+    let Some((player, rare)) = world
+        .query_mut::<(&Player, &Position, Option<&Charm>)>()
+        .into_iter()
+        .next()
+        .map(|(_, (_, position, charm))| (position.0, charm.is_some_and(|charm| charm.kind == CharmKind::Rare)))
+    else {
+        return;
+    };
+    let mut taken: Vec<Vec2> = world
+        .query_mut::<&Position>()
+        .with::<&Collision>()
+        .into_iter()
+        .map(|(_, position)| position.0.round_to_tile())
+        .collect();
+    let mut roaming: Vec<usize> = vec![];
+    for (_, (position, wild)) in world.query_mut::<(&Position, &WildStray)>() {
+        taken.push(position.0.round_to_tile());
+        roaming.push(wild.zone);
+    }
+
+    let mut rng = rand::thread_rng();
+    for (index, zone) in map.encounter_zones.iter().enumerate() {
+        let missing = zone
+            .roaming
+            .saturating_sub(roaming.iter().filter(|&&zone| zone == index).count());
+        if missing == 0 {
+            continue;
+        }
+
+        let tiles: Vec<Vec2> = (zone.y..zone.y + zone.height)
+            .flat_map(|y| (zone.x..zone.x + zone.width).map(move |x| Vec2::from((x, y))))
+            .filter(|&tile| {
+                map.encounter_kind(tile).as_ref() == Some(&zone.kind)
+                    && tile.dist(player) >= WILD_STRAY_SPAWN_DISTANCE
+                    && !taken.contains(&tile)
+            })
+            .collect();
+        for &tile in tiles.choose_multiple(&mut rng, missing) {
+            let Some(stray) = zone.roll_stray(&mut rng, rare) else {
+                eprintln!("map {}: encounter zone at ({}, {}) has no valid strays", map.id, zone.x, zone.y);
+                break;
+            };
+            world.spawn((
+                MapEntity,
+                //position
+                Position(tile),
+                //movement
+                MovingEntity::new(),
+                //texture for entity, swapped for the stray's sprite when it is drawn
+                Sprite::default(),
+                //initial animation state
+                HumanWalkAnimation {
+                    rotation: Direction::Down,
+                    time: (1.0, 0.0),
+                    left_leg: true,
+                    sprinting: false,
+                },
+                //the stray itself, battled when touched
+                WildStray {
+                    stray,
+                    zone: index,
+                    wait: WILD_STRAY_STEP_DELAY,
+                },
+            ));
+        }
+    }
+}
+
+//function for removing every entity spawned by the map being left
+//takes in the world
+pub fn despawn_map_entities(world: &mut World) {
+    let entities: Vec<_> = world
+        .query_mut::<&MapEntity>()
+        .into_iter()
+        .map(|(entity, _)| entity)
+        .collect();
+
+    for entity in entities {
+        world.despawn(entity).unwrap();
+    }
+}
@@ -5,16 +5,37 @@
 /****************************************************/
 use std::collections::HashMap;
 
+use rand::seq::SliceRandom;
+
 use crate::{
     components::{
         animation::{HumanAnimationType, HumanWalkAnimation},
-        Collision, MovingEntity, MovingState, Position, Npc,
+        Charm, CharmKind, Collision, Follower, IdleBehavior, MoveGoal, MovingEntity, MovingState, Position, Npc,
+        Surfing, WildStray,
     },
-    constants::{ROTATION_TIME, RUN_SPEED, WALK_SPEED},
+    constants::{ROTATION_TIME, RUN_SPEED, SURF_SPEED, WALK_SPEED},
+    font_manager::FontManager,
     gamestate::{event::Event, Screen},
+    menu::textbox::Textbox,
+    pathfinding::find_path,
     vec2::{compute_direction, Direction, Vec2},
 };
 
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+//what an NPC with a goal does next
+enum GoalStep {
+    Arrived,           //the NPC reached its goal tile
+    Walk(Direction),   //the next step on the way to the goal
+    Face(Direction),   //the NPC is next to who it is following and turns to them
+    Wait,              //there is no way to the goal right now
+}
+
 use super::State;
 
 impl State {
@@ -26,6 +47,11 @@ impl State {
         for (_, anim) in animation_query.iter() {
             anim.update(delta_time);
         }
+
+        //animated tiles share one clock for the whole map
+        if let Screen::Overworld(map) = &mut self.screen {
+            map.animation_time += delta_time;
+        }
     }
 
     //pub fn update_screen(&mut self, delta_time: f32) {}
@@ -59,6 +85,40 @@ impl State {
         }
     }
 
+    //counts down the time left on the player's spirit charm, if it is a timed one
+    //takes in the time since the last update and the font manager
+    pub fn update_charm(&mut self, delta_time: f32, font_man: &FontManager) {
+        let Ok(charm) = self.world.query_one_mut::<&mut Charm>(self.player) else { return };
+        let Some(time) = &mut charm.time else { return };
+        *time -= delta_time;
+        if *time <= 0.0 {
+            self.end_charm(font_man);
+        }
+    }
+
+    //counts a step of the player against their spirit charm
+    //returns the kind of charm that applies to this step, which still counts on the step it wears off
+    pub fn step_charm(&mut self, font_man: &FontManager) -> Option<CharmKind> {
+        let charm = self.world.query_one_mut::<&mut Charm>(self.player).ok()?;
+        let kind = charm.kind;
+        if let Some(steps) = &mut charm.steps {
+            *steps = steps.saturating_sub(1);
+            if *steps == 0 {
+                self.end_charm(font_man);
+            }
+        }
+        Some(kind)
+    }
+
+    //takes away the player's spirit charm, saying that it wore off
+    fn end_charm(&mut self, font_man: &FontManager) {
+        if let Ok(charm) = self.world.remove_one::<Charm>(self.player) {
+            self.menus.open_menu(
+                Textbox::new(&format!("The {} wore off.", charm.kind), font_man).into(),
+            );
+        }
+    }
+
     //updates player moving state
     //takes in the moving_state (how the player should currently be moving)
     pub fn update_player_moving(&mut self, moving_state: MovingState) {
@@ -81,6 +141,160 @@ impl State {
         moving.try_sprinting = sprinting;
     }
 
+    //walks every NPC that has a goal one step closer to it
+    //a new path is found at every tile, so NPCs go around anything that got in their way since their last step
+    pub fn update_npc_goals(&mut self) {
+        let Screen::Overworld(_) = &self.screen else { panic!(); };
+
+        let mut steps = vec![];
+        for (id, (Position(pos), moving, npc)) in self
+            .world
+            .query::<(&Position, &MovingEntity, &Npc)>()
+            .iter()
+        {
+            //NPCs only choose where to go while standing on a tile, and not while being talked to
+            if matches!(moving.moving, MovingState::Moving(_)) || moving.forced.is_some() || npc.talking {
+                continue;
+            }
+
+            let pos = pos.round_to_tile();
+            let step = match npc.goal {
+                None => continue,
+                Some(MoveGoal::Tile(tile)) if pos == tile.round_to_tile() => GoalStep::Arrived,
+                Some(MoveGoal::Tile(tile)) => self.next_step(pos, tile),
+                Some(MoveGoal::Follow(entity)) => match self.world.get::<&Position>(entity) {
+                    //standing next to who they follow, the NPC waits for them to move
+                    Ok(target) if pos.dist2(target.0.round_to_tile()) <= 1.0 => {
+                        GoalStep::Face(compute_direction(pos, target.0))
+                    }
+                    Ok(target) => self.next_step(pos, target.0),
+                    //the entity being followed is gone
+                    Err(_) => GoalStep::Arrived,
+                },
+            };
+            steps.push((id, step));
+        }
+
+        for (id, step) in steps {
+            let Ok((moving, animation, npc)) = self
+                .world
+                .query_one_mut::<(&mut MovingEntity, &mut HumanWalkAnimation, &mut Npc)>(id)
+            else {
+                continue;
+            };
+
+            moving.try_moving = match step {
+                GoalStep::Arrived => {
+                    //NPCs with a walking path go back to walking it
+                    npc.goal = None;
+                    match &npc.path {
+                        Some(path) => MovingState::Moving(path.direction()),
+                        None => MovingState::Idle,
+                    }
+                }
+                GoalStep::Walk(direction) => MovingState::Moving(direction),
+                GoalStep::Face(direction) => {
+                    moving.rotation = direction;
+                    animation.rotation = direction;
+                    MovingState::Idle
+                }
+                GoalStep::Wait => MovingState::Idle,
+            };
+        }
+    }
+
+    //runs what every NPC without a goal does while standing on a tile: walking its path, wandering or looking around
+    //NPCs being talked to turn to face the player and stay put until the dialogue is closed
+    pub fn update_npc_behaviors(&mut self, delta_time: f32) {
+        let Screen::Overworld(map) = &self.screen else { panic!(); };
+
+        let collisions = &self.collisions;
+        let blocked = |pos: Vec2| {
+            map.check_collision(pos) || collisions.contains_key(&pos.to_usize(map.size_x))
+        };
+        let rng = &mut self.rng;
+        let menus_open = self.menus.is_open();
+        let Ok(player_pos) = self.world.get::<&Position>(self.player).map(|pos| pos.0) else {
+            return;
+        };
+
+        for (_, (Position(pos), moving, animation, npc)) in self
+            .world
+            .query::<(&Position, &mut MovingEntity, &mut HumanWalkAnimation, &mut Npc)>()
+            .iter()
+        {
+            //the dialogue has been closed
+            if npc.talking && !menus_open {
+                npc.talking = false;
+            }
+
+            //NPCs can only stop or turn once they have finished their step
+            let standing = !matches!(moving.moving, MovingState::Moving(_)) && moving.forced.is_none();
+            if npc.talking {
+                moving.try_moving = MovingState::Idle;
+                if standing {
+                    let facing = compute_direction(*pos, player_pos);
+                    moving.rotation = facing;
+                    animation.rotation = facing;
+                }
+                continue;
+            }
+            if npc.goal.is_some() || !standing {
+                continue;
+            }
+
+            npc.wait -= delta_time;
+            if npc.wait > 0.0 {
+                continue;
+            }
+
+            match (&npc.path, &npc.idle) {
+                (Some(path), _) => {
+                    if !path.finished {
+                        moving.try_moving = MovingState::Moving(path.direction());
+                    }
+                }
+                (None, IdleBehavior::Stand) => {}
+                (None, IdleBehavior::Wander { home, radius }) => {
+                    //random step that keeps the NPC within the radius of its home
+                    let options: Vec<Direction> = DIRECTIONS
+                        .into_iter()
+                        .filter(|&direction| {
+                            let next = pos.round_to_tile().step(direction);
+                            (next.0 - home.0).abs() <= *radius as f32
+                                && (next.1 - home.1).abs() <= *radius as f32
+                                && !blocked(next)
+                        })
+                        .collect();
+                    match options.choose(rng) {
+                        Some(&direction) => moving.try_moving = MovingState::Moving(direction),
+                        None => npc.wait = npc.step_delay,
+                    }
+                }
+                (None, IdleBehavior::LookAround) => {
+                    let options: Vec<Direction> = DIRECTIONS
+                        .into_iter()
+                        .filter(|&direction| direction != moving.rotation)
+                        .collect();
+                    if let Some(&direction) = options.choose(rng) {
+                        moving.rotation = direction;
+                        animation.rotation = direction;
+                    }
+                    npc.wait = npc.step_delay;
+                }
+            }
+        }
+    }
+
+    //finds the first step on the way from one tile to another, around walls and other entities
+    fn next_step(&self, from: Vec2, to: Vec2) -> GoalStep {
+        match find_path(from, to, |tile| self.check_collision(&tile)) {
+            Some(path) if !path.is_empty() => GoalStep::Walk(path[0]),
+            Some(_) => GoalStep::Arrived,
+            None => GoalStep::Wait,
+        }
+    }
+
     //runs physics for every object that is capable of moving
     //takes in delta_time (how much to increment the time by each update)
     pub fn update_moving_objects(&mut self, delta_time: f32) {
@@ -93,46 +307,77 @@ impl State {
             &mut MovingEntity,
             &mut HumanWalkAnimation,
             Option<&Npc>,
+            Option<&Surfing>,
+            Option<&Follower>,
+            Option<&WildStray>,
         )>();
-        for (id, (pos, moving, animation, npc)) in moving_query.iter() {
-            //update rotation state if entity is idle
-            if let (Idle, Moving(rotation)) = (moving.moving, moving.try_moving) {
-                if rotation != moving.rotation && moving.rotation_timer >= ROTATION_TIME {
-                    moving.rotation = rotation;
-                    moving.rotation_timer = 0.0;
-                    animation.play_animation(HumanAnimationType::Rotate, rotation);
+        for (id, (pos, moving, animation, npc, surfing, follower, wild)) in moving_query.iter() {
+            //entities riding a water stray can also move over water
+            //and the stray following the player only walks where the player has been, so nothing else blocks it
+            //while wild strays keep to the tiles of the zone they live in
+            let check_collision = |position: &Vec2| match (surfing, follower, wild) {
+                (_, _, Some(wild)) => self.check_wild_collision(wild.zone, position),
+                (_, Some(_), None) => map.check_collision(*position),
+                (Some(_), None, None) => self.check_surf_collision(position),
+                (None, None, None) => self.check_collision(position),
+            };
+
+            //tiles such as ice and spin tiles move the entity no matter what it is trying to do
+            let try_moving = match moving.forced {
+                Some(direction) => Moving(direction),
+                None => moving.try_moving,
+            };
+
+            //entities pushed by a tile turn instantly, everyone else has to rotate first
+            if moving.forced.is_none() {
+                //update rotation state if entity is idle
+                if let (Idle, Moving(rotation)) = (moving.moving, try_moving) {
+                    if rotation != moving.rotation && moving.rotation_timer >= ROTATION_TIME {
+                        moving.rotation = rotation;
+                        moving.rotation_timer = 0.0;
+                        animation.play_animation(HumanAnimationType::Rotate, rotation);
+                    }
+                }
+                //increment rotation time and don't allow movement if rotation is not finished
+                if moving.rotation_timer < ROTATION_TIME {
+                    moving.rotation_timer += delta_time;
+                    continue;
                 }
-            }
-            //increment rotation time and don't allow movement if rotation is not finished
-            if moving.rotation_timer < ROTATION_TIME {
-                moving.rotation_timer += delta_time;
-                continue;
             }
 
             //if rotation is finished, we can update the moving state of the entity
-            match (moving.moving, moving.try_moving) {
+            match (moving.moving, try_moving) {
                 //if the entity is idle and wants to be idle, we don't do any updates
                 (Idle, Idle) | (CenterTile, Idle) => {
                     continue;
                 }
                 //if the entity is idle and wants to be moving, we update the moving state and play the new animation
                 (Idle, Moving(new_rotation)) | (CenterTile, Moving(new_rotation)) => {
-                    //update sprinting state
-                    moving.sprinting = moving.try_sprinting;
+                    //update sprinting state, entities can't start running while being pushed but keep their speed through a hop
+                    moving.sprinting = match (moving.forced, moving.hop) {
+                        (Some(_), None) => false,
+                        (Some(_), Some(_)) => moving.sprinting,
+                        (None, _) => moving.try_sprinting,
+                    };
 
                     let &mut Position(Vec2(x, y)) = pos;
 
-                    let Vec2(target_x, target_y) = match new_rotation {
+                    let target = match new_rotation {
                         Direction::Left => Vec2((x - 1.0).ceil(), y),
                         Direction::Right => Vec2((x + 1.0).floor(), y),
                         Direction::Up => Vec2(x, (y - 1.0).ceil()),
                         Direction::Down => Vec2(x, (y + 1.0).floor()),
                     };
 
-                    //we can only play a new animation if the animation isn't playing already, or if we are changing rotations
-                    //note essentially these conditions could also be read
-                    //entity is idle OR entity is in center of tile (i.e. is trying to move to a wall so can rotate freely)
-                    if !animation.is_playing() || moving.rotation != new_rotation {
+                    if moving.forced.is_some() || surfing.is_some() {
+                        //entities pushed by a tile or riding a stray slide along without walking
+                        moving.rotation = new_rotation;
+                        moving.moving = Moving(new_rotation);
+                        animation.rotation = new_rotation;
+                    } else if !animation.is_playing() || moving.rotation != new_rotation {
+                        //we can only play a new animation if the animation isn't playing already, or if we are changing rotations
+                        //note essentially these conditions could also be read
+                        //entity is idle OR entity is in center of tile (i.e. is trying to move to a wall so can rotate freely)
                         moving.rotation = new_rotation;
                         moving.moving = Moving(new_rotation);
                         let animation_type = match moving.sprinting {
@@ -142,12 +387,24 @@ impl State {
                         animation.play_animation(animation_type, moving.rotation);
                     }
 
-                    if self.check_collision(&(target_x, target_y).into())
-                        || moving.moving == MovingState::CenterTile
+                    //walking into a ledge in its direction hops over it, as long as there is room to land
+                    let hop = moving.forced.is_none()
+                        && map.properties_at(target).ledge == Some(new_rotation)
+                        && !check_collision(&target.step(new_rotation));
+
+                    if moving.moving == MovingState::CenterTile
+                        || !hop && check_collision(&target)
                     {
+                        //being blocked stops any push from the tile underneath
                         moving.moving = MovingState::CenterTile;
+                        moving.forced = None;
+                        moving.hop = None;
                         continue;
                     }
+
+                    if hop {
+                        moving.hop = Some(0.0);
+                    }
                 }
                 _ => (),
             }
@@ -162,10 +419,13 @@ impl State {
                 Idle | CenterTile => panic!("Should not happen"),
             };
 
+            //tiles like deep snow slow down entities walking onto them
             let speed = delta_time
-                * match moving.sprinting {
-                    true => RUN_SPEED,
-                    false => WALK_SPEED,
+                * map.properties_at(Vec2(target_x, target_y)).speed
+                * match (surfing, moving.sprinting) {
+                    (Some(_), _) => SURF_SPEED,
+                    (None, true) => RUN_SPEED,
+                    (None, false) => WALK_SPEED,
                 };
 
             let (dx, dy) = match compute_direction(Vec2(x, y), Vec2(target_x, target_y)) {
@@ -177,6 +437,9 @@ impl State {
 
             //set new position
             *pos = Position(Vec2(x + dx, y + dy));
+            if let Some(travelled) = &mut moving.hop {
+                *travelled += speed;
+            }
 
             let &mut Position(Vec2(x, y)) = pos;
 
@@ -186,11 +449,29 @@ impl State {
                 || dy != 0.0 && (target_y - y).signum() != dy.signum()
             {
                 *pos = Position(Vec2(target_x, target_y));
-                moving.moving = match moving.try_moving {
-                    Idle => Idle,
+
+                //halfway through a hop the entity is above the ledge and carries on over it
+                //otherwise the tile it arrived on can keep pushing it, in the spin direction or onwards over ice
+                let properties = map.properties_at(Vec2(target_x, target_y));
+                let mid_hop = moving.hop.is_some() && properties.ledge.is_some();
+                if mid_hop {
+                    moving.forced = Some(moving.rotation);
+                } else {
+                    moving.hop = None;
+                    moving.forced = properties
+                        .spin
+                        .or(properties.slippery.then_some(moving.rotation));
+                }
+
+                moving.moving = match (moving.forced, moving.try_moving) {
+                    (None, Idle) => Idle,
                     _ => CenterTile,
                 };
 
+                //nothing happens on top of a ledge, only where the entity lands
+                if mid_hop {
+                    continue;
+                }
                 if id == self.player {
                     self.events
                         .push(Event::PlayerMoved(Vec2(target_x, target_y)))
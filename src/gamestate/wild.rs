@@ -0,0 +1,152 @@
+/****************************************************/
+// Description: Wild strays roaming encounter zones in plain sight, wandering, chasing or running
+// from the player depending on their temperament, and battled when the player touches one
+/****************************************************/
+use hecs::Entity;
+use rand::seq::SliceRandom;
+
+use crate::{
+    components::{
+        animation::HumanWalkAnimation, stray::Temperament, Charm, CharmKind, MovingEntity, MovingState,
+        Position, WildStray,
+    },
+    constants::{WILD_STRAY_SIGHT, WILD_STRAY_STEP_DELAY},
+    vec2::{Direction, Vec2},
+};
+
+use super::{Screen, State};
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+//what a wild stray does next, once it is standing on a tile
+enum WildStep {
+    Wait,                  //still waiting before its next wandering step
+    Walk(Direction, bool), //steps in a direction, running if it has noticed the player
+    Stop,                  //there is nowhere for the stray to go
+}
+
+impl State {
+    //walks every wild stray roaming the map: calm strays wander around, while the others chase or run from
+    //the player once they are close enough to see them, and a stray touching the player starts a battle with it
+    //repelled by the spirits' energy, even aggressive strays keep their distance
+    pub fn update_wild_strays(&mut self, delta_time: f32) {
+        let Screen::Overworld(_) = &self.screen else { return };
+        let Ok(player) = self.world.get::<&Position>(self.player).map(|pos| pos.0) else { return };
+
+        //strays can't run into the player during a script or while a menu is open
+        if self.script.is_none() && !self.menus.is_open() {
+            let touched = self
+                .world
+                .query_mut::<(&Position, &WildStray)>()
+                .into_iter()
+                .find(|(_, (position, _))| position.0.dist(player) < 1.0)
+                .map(|(id, (_, wild))| (id, wild.stray.clone()));
+            if let Some((id, stray)) = touched {
+                //the stray is taken into the battle, and only leaves the map if the battle starts
+                if self.start_battle([Some(stray), None, None, None], 0) {
+                    let _ = self.world.despawn(id);
+                    return;
+                }
+            }
+        }
+
+        let repelled = self
+            .world
+            .get::<&Charm>(self.player)
+            .is_ok_and(|charm| charm.kind == CharmKind::Repel);
+        let player = player.round_to_tile();
+
+        let mut strays: Vec<(Entity, Vec2, usize, Temperament)> = vec![];
+        for (id, (Position(pos), moving, wild)) in self
+            .world
+            .query_mut::<(&Position, &mut MovingEntity, &mut WildStray)>()
+        {
+            //strays only pick where to go next once they are standing on a tile
+            if matches!(moving.moving, MovingState::Moving(_)) {
+                continue;
+            }
+            strays.push((id, pos.round_to_tile(), wild.zone, wild.stray.temperament()));
+        }
+
+        for (id, pos, zone, temperament) in strays {
+            let distance = pos.dist(player);
+            let noticed = distance <= WILD_STRAY_SIGHT;
+            //chasing strays step closer to the player and fleeing ones step away, the rest wander anywhere
+            let options: Vec<Direction> = DIRECTIONS
+                .into_iter()
+                .filter(|&direction| {
+                    let next = pos.step(direction);
+                    !self.check_wild_collision(zone, &next)
+                        && match (temperament, noticed, repelled) {
+                            (Temperament::Calm, ..) | (_, false, _) => true,
+                            (Temperament::Aggressive, true, false) => next.dist(player) < distance,
+                            (Temperament::Timid, true, _) | (Temperament::Aggressive, true, true) => {
+                                next.dist(player) > distance
+                            }
+                        }
+                })
+                .collect();
+
+            let Ok((moving, animation, wild)) = self
+                .world
+                .query_one_mut::<(&mut MovingEntity, &mut HumanWalkAnimation, &mut WildStray)>(id)
+            else {
+                continue;
+            };
+
+            let step = match (temperament, noticed) {
+                (Temperament::Timid | Temperament::Aggressive, true) => match options.choose(&mut self.rng) {
+                    Some(&direction) => WildStep::Walk(direction, true),
+                    None => WildStep::Stop,
+                },
+                _ => {
+                    wild.wait -= delta_time;
+                    match (wild.wait > 0.0, options.choose(&mut self.rng)) {
+                        (true, _) => WildStep::Wait,
+                        (false, Some(&direction)) => {
+                            wild.wait = WILD_STRAY_STEP_DELAY;
+                            WildStep::Walk(direction, false)
+                        }
+                        (false, None) => {
+                            wild.wait = WILD_STRAY_STEP_DELAY;
+                            WildStep::Stop
+                        }
+                    }
+                }
+            };
+
+            match step {
+                WildStep::Walk(direction, running) => {
+                    //strays turn without stopping, so they can keep up a chase
+                    moving.rotation = direction;
+                    animation.rotation = direction;
+                    moving.try_moving = MovingState::Moving(direction);
+                    moving.try_sprinting = running;
+                }
+                WildStep::Wait | WildStep::Stop => moving.try_moving = MovingState::Idle,
+            }
+        }
+    }
+
+    //checks collision for a wild stray, which only walks on its zone's kind of tiles, such as grass or water
+    //the player doesn't block wild strays, as touching them is how a battle starts
+    pub fn check_wild_collision(&self, zone: usize, position: &Vec2) -> bool {
+        let Screen::Overworld(map) = &self.screen else { panic!(); };
+        let Some(zone) = map.encounter_zones.get(zone) else { return true };
+        let in_bounds = position.0 >= 0.0
+            && position.1 >= 0.0
+            && position.0 < map.size_x as f32
+            && position.1 < map.size_y as f32;
+        !in_bounds
+            || map.encounter_kind(*position).as_ref() != Some(&zone.kind)
+            || self
+                .collisions
+                .get(&position.to_usize(map.size_x))
+                .is_some_and(|&entity| entity != self.player)
+    }
+}
@@ -0,0 +1,21 @@
+/****************************************************/
+// Description: Library root exposing the game modules to the game binary and the map tools
+/****************************************************/
+pub mod components;
+pub mod constants;
+pub mod font_manager;
+pub mod gamestate;
+pub mod menu;
+pub mod pathfinding;
+pub mod quest;
+pub mod render;
+pub mod resource_manager;
+pub mod script;
+pub mod tilemap;
+pub mod vec2;
+
+extern crate enum_map;
+extern crate sdl2;
+
+#[macro_use]
+extern crate num_derive;
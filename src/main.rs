@@ -2,25 +2,12 @@
 // Created by: Logan Schmalz
 // Description: Logic for the main loop of the game which intializes and updates the game
 /****************************************************/
-mod components;
-mod constants;
-mod font_manager;
-mod gamestate;
-mod menu;
-mod render;
-mod resource_manager;
-mod tilemap;
-mod vec2;
-
-use crate::{gamestate::State, resource_manager::TextureManager};
-
-extern crate sdl2;
-extern crate enum_map;
-
-#[macro_use]
-extern crate num_derive;
-
-use tilemap::TileMap;
+use rustymon::{
+    font_manager,
+    gamestate::State,
+    render,
+    resource_manager::TextureManager,
+};
 
 pub fn main() -> Result<(), String> {
     //creating sdl context, window, canvas
@@ -57,10 +44,7 @@ pub fn main() -> Result<(), String> {
     let font_manager = font_manager::FontManager::new(fonts);
 
     //create engine renderer
-    let mut renderer = render::Renderer::new(canvas);
-
-    //load starting map
-    let mut map = TileMap::load(0);
+    let mut renderer = render::Renderer::new(canvas)?;
 
     //create default gamestate
     let mut state = State {
@@ -83,14 +67,13 @@ pub fn main() -> Result<(), String> {
             break 'running;
         }
         //update gamestate
-        state.update(delta_time, &mut map, &font_manager)?;
+        state.update(delta_time, &font_manager)?;
         //render game
         state.render(
             &mut renderer,
             &mut texture_manager,
             &font_manager,
             delta_time,
-            &mut map,
         )?;
     }
 
@@ -0,0 +1,60 @@
+/****************************************************/
+// Description: Menu logic for dialogue choices, a question in a textbox with a list of answers to pick from
+/****************************************************/
+use hecs::World;
+
+use crate::font_manager::FontManager;
+use crate::gamestate::event::Event;
+
+use super::menu_events::{MenuCommand, MenuInput};
+use super::textbox::Textbox;
+use super::MenuItem;
+
+pub struct ChoiceMenu {
+    pub question: Textbox, //only the first page of the question is shown, next to the options
+    pub options: Vec<String>,
+    pub selected: usize,
+}
+
+impl ChoiceMenu {
+    pub fn new(speaker: Option<String>, question: &str, options: Vec<String>, font_man: &FontManager) -> ChoiceMenu {
+        ChoiceMenu {
+            question: Textbox::with_pages(speaker, &[question.to_string()], font_man),
+            options,
+            selected: 0,
+        }
+    }
+}
+
+impl MenuItem for ChoiceMenu {
+    //the menu update logic
+    //picking an option sends its index to the running script, backing out picks the last option (such as "No")
+    fn update(&mut self, action: MenuInput, _: &mut World, events: &mut Vec<Event>) -> Option<MenuCommand> {
+        match action {
+            MenuInput::Down => {
+                self.selected = if self.selected < self.options.len() - 1 {
+                    self.selected + 1
+                } else {
+                    0
+                }
+            }
+            MenuInput::Up => {
+                self.selected = if self.selected > 0 {
+                    self.selected - 1
+                } else {
+                    self.options.len() - 1
+                }
+            }
+            MenuInput::Accept => {
+                events.push(Event::DialogueChoice(self.selected));
+                return Some(MenuCommand::Close);
+            }
+            MenuInput::Reject => {
+                events.push(Event::DialogueChoice(self.options.len() - 1));
+                return Some(MenuCommand::Close);
+            }
+            _ => {}
+        }
+        None
+    }
+}
@@ -0,0 +1,86 @@
+/****************************************************/
+// Description: Menu logic for the stray encyclopedia, which lists every species the player has seen or caught
+/****************************************************/
+use hecs::{Entity, World};
+
+use crate::components::{encyclopedia::Encyclopedia, stray::SPECIES};
+use crate::font_manager::FontManager;
+use crate::gamestate::event::Event;
+use crate::render::PIXELS_X;
+
+use super::{
+    menu_events::{MenuCommand, MenuInput},
+    MenuItem,
+};
+
+//space in pixels left on either side of a species description, which is drawn over the whole screen
+pub const DESCRIPTION_MARGIN: u32 = 10;
+
+//the encyclopedia menu contains the currently selected species, the entity whose encyclopedia is open,
+//and the species descriptions already broken into lines
+pub struct EncyclopediaMenu {
+    pub selected: usize,
+    pub entity: Entity,
+    pub descriptions: Vec<Vec<String>>,
+    pub showing_description: bool,
+}
+
+impl EncyclopediaMenu {
+    pub fn new(entity: Entity, font_man: &FontManager) -> EncyclopediaMenu {
+        let descriptions = SPECIES
+            .iter()
+            .map(|species| font_man.break_string(species.description, PIXELS_X - 2 * DESCRIPTION_MARGIN))
+            .collect();
+
+        EncyclopediaMenu {
+            selected: 0,
+            entity,
+            descriptions,
+            showing_description: false,
+        }
+    }
+}
+
+impl MenuItem for EncyclopediaMenu {
+    //the menu update logic
+    fn update(&mut self, action: MenuInput, world: &mut World, _: &mut Vec<Event>) -> Option<MenuCommand> {
+        let length = SPECIES.len();
+
+        //while a description is open, any button returns to the species list
+        if self.showing_description {
+            if matches!(action, MenuInput::Accept | MenuInput::Reject) {
+                self.showing_description = false;
+            }
+            return None;
+        }
+
+        match action {
+            MenuInput::Down => {
+                self.selected = if self.selected < length - 1 {
+                    self.selected + 1
+                } else {
+                    0
+                }
+            }
+            MenuInput::Up => {
+                self.selected = if self.selected > 0 {
+                    self.selected - 1
+                } else {
+                    length - 1
+                }
+            }
+            MenuInput::Accept => {
+                //the description is known once a stray of the species has been seen
+                let species = &SPECIES[self.selected];
+                if let Ok(encyclopedia) = world.query_one_mut::<&Encyclopedia>(self.entity) {
+                    self.showing_description = encyclopedia.is_seen(species.name);
+                }
+            }
+            MenuInput::Reject => {
+                return Some(MenuCommand::Close);
+            }
+            _ => {}
+        }
+        None
+    }
+}
@@ -20,6 +20,8 @@ pub enum MenuInput {
 pub enum MenuCommand {
     OpenStrays,
     OpenBag(Entity),
+    OpenEncyclopedia(Entity),
+    OpenQuestLog(Entity),
     OpenSave,
     Close,
     OpenTextbox(String),
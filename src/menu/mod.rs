@@ -4,10 +4,14 @@
 // including the menu manager data structure
 /****************************************************/
 pub mod bag_menu;
+pub mod choice_menu;
+pub mod encyclopedia_menu;
 pub mod main_menu;
 pub mod menu_events;
 pub mod moves_menu;
 pub mod pause_menu;
+pub mod quest_log_menu;
+pub mod shop_menu;
 pub mod textbox;
 
 use hecs::World;
@@ -16,10 +20,14 @@ use crate::font_manager::FontManager;
 use crate::gamestate::event::Event;
 
 use self::bag_menu::BagMenu;
+use self::choice_menu::ChoiceMenu;
+use self::encyclopedia_menu::EncyclopediaMenu;
 use self::main_menu::MainMenu;
 use self::menu_events::{MenuCommand, MenuInput};
 use self::moves_menu::MovesMenu;
 use self::pause_menu::PauseMenu;
+use self::quest_log_menu::QuestLogMenu;
+use self::shop_menu::ShopMenu;
 use self::textbox::Textbox;
 
 #[enum_delegate::register]
@@ -36,9 +44,13 @@ pub trait MenuItem {
 pub enum Menu {
     MainMenu(MainMenu),
     Textbox(Textbox),
+    ChoiceMenu(ChoiceMenu),
     PauseMenu(PauseMenu),
     BagMenu(BagMenu),
     MovesMenu(MovesMenu),
+    EncyclopediaMenu(EncyclopediaMenu),
+    QuestLogMenu(QuestLogMenu),
+    ShopMenu(ShopMenu),
 }
 
 pub struct MenuManager {
@@ -101,13 +113,19 @@ impl MenuManager {
     fn process_command(
         &mut self,
         command: MenuCommand,
-        _world: &mut World,
+        world: &mut World,
         font_manager: &FontManager,
     ) -> bool {
         match command {
             MenuCommand::OpenStrays => {}
             MenuCommand::OpenSave => {}
             MenuCommand::OpenBag(entity) => self.open_menu(BagMenu::new(entity).into()),
+            MenuCommand::OpenEncyclopedia(entity) => {
+                self.open_menu(EncyclopediaMenu::new(entity, font_manager).into())
+            }
+            MenuCommand::OpenQuestLog(entity) => {
+                self.open_menu(QuestLogMenu::new(entity, world, font_manager).into())
+            }
             MenuCommand::Close => {
                 return self.close_menu();
             }
@@ -4,7 +4,7 @@
 /****************************************************/
 use hecs::World;
 
-use crate::components::{bag::Bag, Player};
+use crate::components::{bag::Bag, encyclopedia::Encyclopedia, flags::Flags, Player};
 use crate::gamestate::event::Event;
 
 use super::{
@@ -23,7 +23,9 @@ impl PauseMenu {
     pub fn new() -> PauseMenu {
         let items = vec![
             String::from("Strays"),
+            String::from("Index"),
             String::from("Bag"),
+            String::from("Quests"),
             String::from("Save"),
             String::from("Options"),
             String::from("Close"),
@@ -58,6 +60,20 @@ impl MenuItem for PauseMenu {
                         return Some(MenuCommand::OpenBag(entity));
                     }
                 }
+                "Index" => {
+                    if let Some((entity, (_, _))) =
+                        world.query_mut::<(&Player, &Encyclopedia)>().into_iter().next()
+                    {
+                        return Some(MenuCommand::OpenEncyclopedia(entity));
+                    }
+                }
+                "Quests" => {
+                    if let Some((entity, (_, _))) =
+                        world.query_mut::<(&Player, &Flags)>().into_iter().next()
+                    {
+                        return Some(MenuCommand::OpenQuestLog(entity));
+                    }
+                }
                 "Close" => return Some(MenuCommand::Close),
                 _ => {}
             },
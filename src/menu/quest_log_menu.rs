@@ -0,0 +1,95 @@
+/****************************************************/
+// Description: Menu logic for the quest log, which lists the quests the player has started and their objectives
+/****************************************************/
+use hecs::{Entity, World};
+
+use crate::components::{bag::Bag, flags::Flags};
+use crate::font_manager::FontManager;
+use crate::gamestate::event::Event;
+use crate::quest::Quest;
+
+use super::{
+    menu_events::{MenuCommand, MenuInput},
+    MenuItem,
+};
+
+//width in pixels the objectives are broken into lines for, the left panel of the menu
+const OBJECTIVE_WIDTH: u32 = 116;
+
+//a quest as it is shown in the log, with its objectives already broken into lines
+pub struct QuestEntry {
+    pub name: String,
+    pub completed: bool,
+    pub objectives: Vec<(Vec<String>, bool)>, //lines of each known objective and whether it is done
+}
+
+//the quest log contains the started quests, active ones first, and the selected quest
+pub struct QuestLogMenu {
+    pub selected: usize,
+    pub quests: Vec<QuestEntry>,
+}
+
+impl QuestLogMenu {
+    //creates the quest log from the story progress of an entity (the player)
+    pub fn new(entity: Entity, world: &World, font_man: &FontManager) -> QuestLogMenu {
+        let quests = Quest::load_all().unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            vec![]
+        });
+
+        let mut entries = vec![];
+        if let Ok(mut query) = world.query_one::<(&Flags, &Bag)>(entity) {
+            if let Some((flags, bag)) = query.get() {
+                for quest in quests.iter().filter(|quest| quest.is_started(flags, bag)) {
+                    entries.push(QuestEntry {
+                        name: quest.name.clone(),
+                        completed: quest.is_completed(flags, bag),
+                        objectives: quest
+                            .progress(flags, bag)
+                            .into_iter()
+                            .map(|(objective, done)| {
+                                (font_man.break_string(&objective.text, OBJECTIVE_WIDTH), done)
+                            })
+                            .collect(),
+                    });
+                }
+            }
+        }
+        //sorting is stable, so quests keep their order within active and completed
+        entries.sort_by_key(|entry| entry.completed);
+
+        QuestLogMenu {
+            selected: 0,
+            quests: entries,
+        }
+    }
+}
+
+impl MenuItem for QuestLogMenu {
+    //the menu update logic
+    fn update(&mut self, action: MenuInput, _: &mut World, _: &mut Vec<Event>) -> Option<MenuCommand> {
+        let length = self.quests.len();
+
+        match action {
+            MenuInput::Down if length > 0 => {
+                self.selected = if self.selected < length - 1 {
+                    self.selected + 1
+                } else {
+                    0
+                }
+            }
+            MenuInput::Up if length > 0 => {
+                self.selected = if self.selected > 0 {
+                    self.selected - 1
+                } else {
+                    length - 1
+                }
+            }
+            MenuInput::Reject => {
+                return Some(MenuCommand::Close);
+            }
+            _ => {}
+        }
+        None
+    }
+}
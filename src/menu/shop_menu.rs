@@ -0,0 +1,155 @@
+/****************************************************/
+// Description: Menu logic for shops, where the player buys a shopkeeper's items and sells items from their bag
+/****************************************************/
+use hecs::{Entity, World};
+
+use crate::components::{
+    bag::{Bag, Item},
+    wallet::Wallet,
+};
+use crate::font_manager::FontManager;
+use crate::gamestate::event::Event;
+
+use super::{
+    menu_events::{MenuCommand, MenuInput},
+    MenuItem,
+};
+
+//width in pixels item descriptions are broken into lines for, the left panel of the menu
+const DESCRIPTION_WIDTH: u32 = 116;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShopMode {
+    Buy,
+    Sell,
+}
+
+//the shop menu contains the shopkeeper's stock, whether the player is buying or selling, and the selected item
+pub struct ShopMenu {
+    pub entity: Entity, //the entity (the player) whose wallet and bag are used
+    pub stock: Vec<(Item, u32)>,
+    pub mode: ShopMode,
+    pub selected: usize,
+    pub message: Option<String>, //what happened with the last item bought or sold
+    descriptions: Vec<(Item, Vec<String>)>,
+}
+
+impl ShopMenu {
+    //creates the shop menu for an entity (the player) from a shopkeeper's stock and prices
+    pub fn new(entity: Entity, stock: Vec<(Item, u32)>, world: &World, font_man: &FontManager) -> ShopMenu {
+        //descriptions are broken into lines up front, for every item that can be bought or sold here
+        let mut items: Vec<Item> = stock.iter().map(|(item, _)| *item).collect();
+        if let Ok(bag) = world.get::<&Bag>(entity) {
+            items.extend(bag.items.iter().map(|(item, _)| *item));
+        }
+        let mut descriptions: Vec<(Item, Vec<String>)> = vec![];
+        for item in items {
+            if !descriptions.iter().any(|(described, _)| *described == item) {
+                descriptions.push((item, font_man.break_string(item.description(), DESCRIPTION_WIDTH)));
+            }
+        }
+
+        ShopMenu {
+            entity,
+            stock,
+            mode: ShopMode::Buy,
+            selected: 0,
+            message: None,
+            descriptions,
+        }
+    }
+
+    //returns the items listed in the current mode and their prices
+    //the sell list is the items in the bag that shops will take
+    pub fn listing(&self, world: &World) -> Vec<(Item, u32)> {
+        match self.mode {
+            ShopMode::Buy => self.stock.clone(),
+            ShopMode::Sell => world.get::<&Bag>(self.entity).map_or(vec![], |bag| {
+                bag.items
+                    .iter()
+                    .filter_map(|(item, _)| item.sell_price().map(|price| (*item, price)))
+                    .collect()
+            }),
+        }
+    }
+
+    //returns the lines of an item's description
+    pub fn description(&self, item: Item) -> &[String] {
+        self.descriptions
+            .iter()
+            .find(|(described, _)| *described == item)
+            .map_or(&[], |(_, lines)| lines)
+    }
+
+    //buys or sells one of the selected item
+    //returns the message saying what happened
+    fn trade(&self, item: Item, price: u32, world: &mut World) -> String {
+        let Ok((wallet, bag)) = world.query_one_mut::<(&mut Wallet, &mut Bag)>(self.entity) else {
+            return String::new();
+        };
+        match self.mode {
+            ShopMode::Buy => match wallet.spend(price) {
+                true => {
+                    bag.add_item(item, 1);
+                    format!("Bought a {}.", item)
+                }
+                false => String::from("Not enough money."),
+            },
+            ShopMode::Sell => match bag.remove_item(item, 1) {
+                1 => {
+                    wallet.add(price);
+                    format!("Sold a {}.", item)
+                }
+                _ => String::new(),
+            },
+        }
+    }
+}
+
+impl MenuItem for ShopMenu {
+    //the menu update logic
+    fn update(&mut self, action: MenuInput, world: &mut World, _: &mut Vec<Event>) -> Option<MenuCommand> {
+        let listing = self.listing(world);
+        let length = listing.len();
+
+        match action {
+            MenuInput::Down if length > 0 => {
+                self.selected = if self.selected < length - 1 {
+                    self.selected + 1
+                } else {
+                    0
+                }
+            }
+            MenuInput::Up if length > 0 => {
+                self.selected = if self.selected > 0 {
+                    self.selected - 1
+                } else {
+                    length - 1
+                }
+            }
+            MenuInput::Left | MenuInput::Right => {
+                self.mode = match self.mode {
+                    ShopMode::Buy => ShopMode::Sell,
+                    ShopMode::Sell => ShopMode::Buy,
+                };
+                self.selected = 0;
+                self.message = None;
+            }
+            MenuInput::Accept => {
+                if let Some(&(item, price)) = listing.get(self.selected) {
+                    self.message = Some(self.trade(item, price, world));
+                    //selling the last of an item takes it off the list
+                    let length = self.listing(world).len();
+                    if self.selected >= length {
+                        self.selected = length.saturating_sub(1);
+                    }
+                }
+            }
+            MenuInput::Reject => {
+                return Some(MenuCommand::Close);
+            }
+            _ => {}
+        }
+        None
+    }
+}
@@ -13,16 +13,29 @@ use super::MenuItem;
 
 pub struct Textbox {
     pub text_v: Vec<String>,
+    pub speaker: Option<String>, //name of who is talking, shown above the text
 }
 
 impl Textbox {
     pub fn new(text_in: &str, font_man: &FontManager) -> Textbox {
-        let mut text_v = font_man.break_string(text_in, PIXELS_X);
-        if (text_v.len() % 2) == 1 {
-            text_v.push(" ".to_string());
+        Textbox::with_pages(None, &[text_in.to_string()], font_man)
+    }
+
+    //creates a textbox with a speaker name, where each page starts on a new box
+    pub fn with_pages(speaker: Option<String>, pages: &[String], font_man: &FontManager) -> Textbox {
+        let mut text_v = vec![];
+        for page in pages {
+            text_v.extend(font_man.break_string(page, PIXELS_X));
+            if (text_v.len() % 2) == 1 {
+                text_v.push(" ".to_string());
+            }
+        }
+        //a textbox without any text still shows an empty box until it is closed
+        if text_v.is_empty() {
+            text_v = vec![" ".to_string(), " ".to_string()];
         }
 
-        Textbox { text_v }
+        Textbox { text_v, speaker }
     }
 }
 
@@ -0,0 +1,141 @@
+/****************************************************/
+// Description: A* pathfinding over the tile grid, used to walk NPCs to a tile or after another entity
+// around walls and anything else in the way
+/****************************************************/
+use std::{cmp::Reverse, collections::HashMap};
+
+use priority_queue::PriorityQueue;
+
+use crate::vec2::{Direction, Vec2};
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+//the most tiles searched before giving up, so an unreachable goal on a big map doesn't stall the game
+const MAX_SEARCHED_TILES: usize = 4096;
+
+//estimated number of steps between two tiles
+fn distance((x1, y1): (i32, i32), (x2, y2): (i32, i32)) -> u32 {
+    x1.abs_diff(x2) + y1.abs_diff(y2)
+}
+
+fn tile(pos: Vec2) -> (i32, i32) {
+    let Vec2(x, y) = pos.round_to_tile();
+    (x as i32, y as i32)
+}
+
+//finds the shortest path between two tiles, moving one tile up, down, left or right at a time
+//takes in the start and goal tiles and a function telling if a tile is blocked
+//the goal itself is never checked, so a path can lead up to a tile something stands on, such as an entity being
+//followed, and the last step onto it is stopped by collision like any other step
+//returns the directions to walk in, or None if the goal can't be reached
+pub fn find_path(start: Vec2, goal: Vec2, blocked: impl Fn(Vec2) -> bool) -> Option<Vec<Direction>> {
+    let (start, goal) = (tile(start), tile(goal));
+
+    //tiles to search, the ones with the lowest estimated total path length first
+    let mut open = PriorityQueue::new();
+    //the fewest steps found so far to each tile, and the tile and direction it was reached from
+    let mut steps: HashMap<(i32, i32), u32> = HashMap::from([(start, 0)]);
+    let mut came_from: HashMap<(i32, i32), ((i32, i32), Direction)> = HashMap::new();
+
+    open.push(start, Reverse(distance(start, goal)));
+    let mut searched = 0;
+    while let Some((current, _)) = open.pop() {
+        if current == goal {
+            //walk back from the goal to rebuild the path
+            let mut path = vec![];
+            let mut at = current;
+            while let Some(&(previous, direction)) = came_from.get(&at) {
+                path.push(direction);
+                at = previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        searched += 1;
+        if searched > MAX_SEARCHED_TILES {
+            return None;
+        }
+
+        for direction in DIRECTIONS {
+            let next = tile(Vec2::from(current).step(direction));
+            if next != goal && blocked(Vec2::from(next)) {
+                continue;
+            }
+
+            let next_steps = steps[&current] + 1;
+            if steps.get(&next).is_none_or(|&known| next_steps < known) {
+                steps.insert(next, next_steps);
+                came_from.insert(next, (current, direction));
+                open.push_increase(next, Reverse(next_steps + distance(next, goal)));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //follows a path from a tile, returning every tile walked onto
+    fn walk(start: Vec2, path: &[Direction]) -> Vec<Vec2> {
+        path.iter()
+            .scan(start, |pos, &direction| {
+                *pos = pos.step(direction);
+                Some(*pos)
+            })
+            .collect()
+    }
+
+    //everything outside a square around the origin is blocked, so searches always end
+    fn outside(pos: Vec2, size: f32) -> bool {
+        pos.0.abs() > size || pos.1.abs() > size
+    }
+
+    #[test]
+    fn straight_path() {
+        let path = find_path(Vec2(0.0, 0.0), Vec2(3.0, 0.0), |_| false);
+        assert_eq!(path, Some(vec![Direction::Right; 3]));
+    }
+
+    #[test]
+    fn detour_around_wall() {
+        let wall = |pos: Vec2| pos.0 == 1.0 && pos.1.abs() <= 1.0;
+        let (start, goal) = (Vec2(0.0, 0.0), Vec2(2.0, 0.0));
+        let path = find_path(start, goal, |pos| wall(pos) || outside(pos, 5.0)).unwrap();
+
+        assert_eq!(path.len(), 6);
+        let tiles = walk(start, &path);
+        assert!(tiles.iter().all(|&pos| !wall(pos)));
+        assert_eq!(tiles.last(), Some(&goal));
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        let goal = Vec2(3.0, 0.0);
+        let walled_in = |pos: Vec2| pos.dist2(goal) == 1.0;
+        assert_eq!(find_path(Vec2(0.0, 0.0), goal, |pos| walled_in(pos) || outside(pos, 5.0)), None);
+    }
+
+    #[test]
+    fn gives_up_after_max_searched_tiles() {
+        let near = Vec2(100.0, 0.0);
+        let far = Vec2((MAX_SEARCHED_TILES + 10) as f32, 0.0);
+        assert_eq!(find_path(Vec2(0.0, 0.0), near, |_| false).map(|path| path.len()), Some(100));
+        assert_eq!(find_path(Vec2(0.0, 0.0), far, |_| false), None);
+    }
+
+    #[test]
+    fn blocked_goal_is_still_reached() {
+        let goal = Vec2(2.0, 0.0);
+        let path = find_path(Vec2(0.0, 0.0), goal, |pos| pos == goal);
+        assert_eq!(path, Some(vec![Direction::Right; 2]));
+    }
+}
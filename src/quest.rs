@@ -0,0 +1,68 @@
+/****************************************************/
+// Description: Data structures for quests, the objectives listed in the quest log
+// a quest is started and its objectives are completed by conditions on the player's story flags and bag
+/****************************************************/
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{bag::Bag, flags::Flags},
+    script::Condition,
+};
+
+pub const QUESTS_PATH: &str = "assets/quests.json"; //every quest in the game, in the order they are listed
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct QuestFile {
+    quests: Vec<Quest>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Quest {
+    pub name: String,
+    #[serde(default)]
+    pub start: Option<Condition>, //the quest shows up in the log once this holds, right away if not given
+    pub objectives: Vec<Objective>, //done in order, each one is shown once the ones before it are done
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Objective {
+    pub text: String,
+    pub done: Condition,
+}
+
+impl Quest {
+    //loads every quest
+    //returns the quests, or a message saying why they could not be read
+    pub fn load_all() -> Result<Vec<Quest>, String> {
+        let text = fs::read_to_string(QUESTS_PATH).map_err(|e| format!("{}: {}", QUESTS_PATH, e))?;
+        serde_json::from_str::<QuestFile>(&text)
+            .map(|file| file.quests)
+            .map_err(|e| format!("{}: {}", QUESTS_PATH, e))
+    }
+
+    //checks if the quest has been started
+    pub fn is_started(&self, flags: &Flags, bag: &Bag) -> bool {
+        self.start.as_ref().is_none_or(|start| start.check(flags, bag))
+    }
+
+    //returns the objectives the player knows about, with whether each is done
+    //that is every objective up to and including the first one not done yet
+    pub fn progress(&self, flags: &Flags, bag: &Bag) -> Vec<(&Objective, bool)> {
+        let mut progress = vec![];
+        for objective in self.objectives.iter() {
+            let done = objective.done.check(flags, bag);
+            progress.push((objective, done));
+            if !done {
+                break;
+            }
+        }
+        progress
+    }
+
+    //checks if every objective of the quest is done
+    pub fn is_completed(&self, flags: &Flags, bag: &Bag) -> bool {
+        self.objectives.iter().all(|objective| objective.done.check(flags, bag))
+    }
+}
@@ -71,7 +71,10 @@ impl Renderer {
         // opponent stray rendering loop
         for (index, stray) in battle.opponent_strays.iter().enumerate() {
             if let Some(stray_data) = stray {
-                if matches!(battle.battle_state, BattleState::SelectingOpponentStray) {
+                if matches!(
+                    battle.battle_state,
+                    BattleState::SelectingOpponentStray | BattleState::SelectingCatchStray
+                ) {
                     if let Some(i) = battle.selected_stray {
                         if i == index {
                             let texture = texture_manager.load("assets/UI/enemy_select.png")?; // texture to select enemy stray
@@ -124,7 +127,7 @@ impl Renderer {
         for (index, stray) in battle.player_strays.iter().enumerate() {
             if let Some(stray_data) = stray {
                 let text_color = // text color for stray name based on whether or not it is their turn currentl
-                if battle.player_strays[battle.turn_order[0]].as_ref().unwrap().species == stray_data.species { //if it is the stray's turn
+                if battle.player_strays[battle.turn_order[0]].as_ref().unwrap().id == stray_data.id { //if it is the stray's turn
                     Color::RGB(167, 84, 94) //red
                 } else {
                     Color::RGB(31, 27, 24) //black
@@ -132,7 +135,7 @@ impl Renderer {
                 let name_surface = font_manager
                     .fonts
                     .munro
-                    .render(&stray_data.species)
+                    .render(stray_data.name())
                     .blended(text_color)
                     .map_err(|e| e.to_string())?;
                 let name = creator
@@ -179,8 +182,8 @@ impl Renderer {
                     if battle.opponent_strays[battle.turn_order[0] - 4]
                         .as_ref()
                         .unwrap()
-                        .species
-                        == stray_data.species
+                        .id
+                        == stray_data.id
                     {
                         //if it is the stray's turn
                         text_color = Color::RGB(167, 84, 94); //red
@@ -190,7 +193,7 @@ impl Renderer {
                 let name_surface = font_manager
                     .fonts
                     .munro
-                    .render(&stray_data.species)
+                    .render(stray_data.name())
                     .blended(text_color)
                     .map_err(|e| e.to_string())?;
                 let name = creator
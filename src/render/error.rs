@@ -0,0 +1,64 @@
+/****************************************************/
+// Description: Logic for rendering the error screen shown when the game cannot continue
+/****************************************************/
+use sdl2::{pixels::Color, rect::Rect};
+
+use crate::font_manager::FontManager;
+
+use super::{Renderer, PIXELS_X, PIXELS_Y};
+
+const PAD: i32 = 6;
+
+impl Renderer {
+    //renders the error screen
+    //takes in the font manager and the message, which is wrapped to fit the screen and cut off at the bottom
+    pub fn render_error(&mut self, font_man: &FontManager, message: &str) -> Result<(), String> {
+        let font = &font_man.fonts.munro;
+        let max_w = PIXELS_X - PAD as u32 * 2;
+
+        //wrap each line of the message at whitespace
+        let mut lines = vec![String::from("Something went wrong:")];
+        for text in message.lines() {
+            let mut line = String::new();
+            for word in text.split_whitespace() {
+                let next = match line.is_empty() {
+                    true => word.to_string(),
+                    false => format!("{} {}", line, word),
+                };
+                let (w, _) = font.size_of(&next).map_err(|e| e.to_string())?;
+                if w > max_w && !line.is_empty() {
+                    lines.push(line);
+                    line = word.to_string();
+                } else {
+                    line = next;
+                }
+            }
+            lines.push(line);
+        }
+
+        self.canvas.set_draw_color(Color::RGB(40, 40, 40));
+        self.canvas.clear();
+
+        let creator = self.canvas.texture_creator();
+        let mut text_quad = Rect::new(PAD, PAD, 0, 0);
+        for line in lines.iter().filter(|line| !line.is_empty()) {
+            if text_quad.y >= PIXELS_Y as i32 - PAD {
+                break;
+            }
+            let surface = font
+                .render(line)
+                .blended(Color::RGB(231, 232, 237))
+                .map_err(|e| e.to_string())?;
+            text_quad.set_width(surface.width());
+            text_quad.set_height(surface.height());
+            let texture = creator
+                .create_texture_from_surface(&surface)
+                .map_err(|e| e.to_string())?;
+            self.canvas.copy(&texture, None, text_quad)?;
+
+            text_quad.set_y(text_quad.y + surface.height() as i32);
+        }
+
+        Ok(())
+    }
+}
@@ -6,13 +6,17 @@ use hecs::World;
 use sdl2::{pixels::Color, rect::Rect, video::WindowContext};
 
 use crate::{
-    components::bag::Bag,
+    components::{bag::Bag, encyclopedia::Encyclopedia, stray::SPECIES, wallet::Wallet},
     font_manager::FontManager,
     menu::{
         bag_menu::BagMenu,
+        choice_menu::ChoiceMenu,
+        encyclopedia_menu::{EncyclopediaMenu, DESCRIPTION_MARGIN},
         main_menu::{MainMenu, MainMenuButton},
         moves_menu::MovesMenu,
         pause_menu::PauseMenu,
+        quest_log_menu::QuestLogMenu,
+        shop_menu::{ShopMenu, ShopMode},
         textbox::Textbox,
     },
     resource_manager::TextureManager,
@@ -208,6 +212,84 @@ impl Renderer {
         self.canvas.copy(&texture_top, None, text_quad_top)?;
         self.canvas.copy(&texture_bot, None, text_quad_bot)?;
 
+        // the speaker's name sits on a small plate on top of the textbox
+        if let Some(speaker) = &textbox.speaker {
+            let surface = font_man
+                .fonts
+                .munro
+                .render(speaker)
+                .blended(Color::RGB(40, 40, 40))
+                .map_err(|e| e.to_string())?;
+            let plate_quad = Rect::new(
+                4,
+                box_quad.y - surface.height() as i32 + 2,
+                surface.width() + 10,
+                surface.height(),
+            );
+            self.render_panel(plate_quad)?;
+
+            let texture = creator
+                .create_texture_from_surface(&surface)
+                .map_err(|e| e.to_string())?;
+            let name_quad = Rect::new(plate_quad.x + 5, plate_quad.y + 1, surface.width(), surface.height());
+            self.canvas.copy(&texture, None, name_quad)?;
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn render_choice_menu(
+        //function for rendering a dialogue choice, the question's textbox with the options above it on the right
+        &mut self,
+        menu: &ChoiceMenu,
+        texture_manager: &mut TextureManager<WindowContext>,
+        font_man: &FontManager,
+    ) -> Result<(), String> {
+        self.render_textbox(&menu.question, texture_manager, font_man)?;
+
+        // render the options, the selected one highlighted like in the pause menu
+        let mut surfaces = vec![];
+        for (idx, option) in menu.options.iter().enumerate() {
+            let surface = font_man.fonts.munro.render(option);
+            let surface = if idx == menu.selected {
+                surface.blended(Color::RGB(0, 183, 235))
+            } else {
+                surface.blended(Color::RGB(40, 40, 40))
+            };
+            surfaces.push(surface.map_err(|e| e.to_string())?);
+        }
+
+        let width = surfaces.iter().map(|surface| surface.width()).max().unwrap_or(0) + 16;
+        let height = surfaces.iter().map(|surface| surface.height()).sum::<u32>() + 8;
+        let panel_quad = Rect::new(
+            PIXELS_X as i32 - width as i32 - 4,
+            (PIXELS_Y - 41) as i32 - height as i32 - 2,
+            width,
+            height,
+        );
+        self.render_panel(panel_quad)?;
+
+        let creator = self.canvas.texture_creator();
+        let mut text_quad = Rect::new(panel_quad.x + 8, panel_quad.y + 4, 0, 0);
+        for surface in surfaces {
+            text_quad.set_width(surface.width());
+            text_quad.set_height(surface.height());
+            let texture = creator
+                .create_texture_from_surface(&surface)
+                .map_err(|e| e.to_string())?;
+            self.canvas.copy(&texture, None, text_quad)?;
+            text_quad.set_y(text_quad.y + surface.height() as i32);
+        }
+
+        Ok(())
+    }
+
+    // draws a plain box with a dark border, matching the colours of the textbox
+    fn render_panel(&mut self, quad: Rect) -> Result<(), String> {
+        self.canvas.set_draw_color(Color::RGB(95, 114, 144));
+        self.canvas.fill_rect(quad)?;
+        self.canvas.set_draw_color(Color::RGB(230, 223, 195));
+        self.canvas.fill_rect(Rect::new(quad.x + 1, quad.y + 1, quad.width() - 2, quad.height() - 2))?;
         Ok(())
     }
 
@@ -301,4 +383,316 @@ impl Renderer {
 
         Ok(())
     }
+
+    pub(super) fn render_encyclopedia_menu(
+        &mut self,
+        menu: &EncyclopediaMenu,
+        world: &World,
+        texture_manager: &mut TextureManager<WindowContext>,
+        font_man: &FontManager,
+    ) -> Result<(), String> {
+        // nothing is drawn for an entity without an encyclopedia
+        let Ok(encyclopedia) = world.get::<&Encyclopedia>(menu.entity) else {
+            return Ok(());
+        };
+        let box_quad = Rect::new(0, 0, PIXELS_X, PIXELS_Y);
+        let creator = self.canvas.texture_creator();
+
+        // the description of a seen species covers the whole screen
+        if menu.showing_description {
+            self.canvas.set_draw_color(Color::RGB(231, 232, 237));
+            self.canvas.fill_rect(box_quad)?;
+
+            let mut text_quad = Rect::new(DESCRIPTION_MARGIN as i32, 8, 0, 0);
+            for line in menu.descriptions[menu.selected].iter() {
+                let surface = font_man
+                    .fonts
+                    .munro
+                    .render(line)
+                    .blended(Color::RGB(40, 40, 40))
+                    .map_err(|e| e.to_string())?;
+                text_quad.set_width(surface.width());
+                text_quad.set_height(surface.height());
+                let texture = creator
+                    .create_texture_from_surface(&surface)
+                    .map_err(|e| e.to_string())?;
+                self.canvas.copy(&texture, None, text_quad)?;
+
+                text_quad.set_y(text_quad.y + surface.height() as i32);
+            }
+            return Ok(());
+        }
+
+        let texture = texture_manager.load("assets/UI/bag_menu.png")?;
+        self.canvas.copy(&texture, None, box_quad)?;
+
+        // the left panel shows whatever is known about the selected species
+        let species = &SPECIES[menu.selected];
+        let mut info_lines = vec![format!("No. {:03}", menu.selected + 1)];
+        if encyclopedia.is_seen(species.name) {
+            let sprite = texture_manager.load(species.texture)?;
+            let slice = Rect::new(0, 0, sprite.query().width / 2, sprite.query().height);
+            self.canvas
+                .copy(&sprite, slice, Rect::new(12, 20, slice.width(), slice.height()))?;
+
+            info_lines.push(species.name.to_string());
+            info_lines.push(format!("{:?} type", species.s_type));
+            info_lines.push("Habitat:".to_string());
+            info_lines.push(species.habitat.to_string());
+            info_lines.push(if encyclopedia.is_caught(species.name) {
+                "Caught".to_string()
+            } else {
+                "Seen".to_string()
+            });
+        } else {
+            info_lines.push("???".to_string());
+        }
+
+        let mut text_quad = Rect::new(6, 6, 0, 0);
+        for (idx, line) in info_lines.iter().enumerate() {
+            let surface = font_man
+                .fonts
+                .munro
+                .render(line)
+                .blended(Color::RGB(231, 232, 237))
+                .map_err(|e| e.to_string())?;
+            text_quad.set_width(surface.width());
+            text_quad.set_height(surface.height());
+            let texture = creator
+                .create_texture_from_surface(&surface)
+                .map_err(|e| e.to_string())?;
+            self.canvas.copy(&texture, None, text_quad)?;
+
+            // leave room for the sprite below the number
+            text_quad.set_y(if idx == 0 { 88 } else { text_quad.y + surface.height() as i32 });
+        }
+
+        // the right panel lists every species
+        let lines: Vec<String> = SPECIES
+            .iter()
+            .enumerate()
+            .map(|(idx, species)| {
+                if encyclopedia.is_seen(species.name) {
+                    format!("{:03} {}", idx + 1, species.name)
+                } else {
+                    format!("{:03} -----", idx + 1)
+                }
+            })
+            .collect();
+
+        let mut text_quad = Rect::new(122, 10, 0, 0);
+        for (idx, line) in lines.iter().enumerate() {
+            let surface = font_man.fonts.munro.render(line);
+            let surface = if idx == menu.selected {
+                surface.blended(Color::RGB(0, 183, 235))
+            } else {
+                surface.blended(Color::RGB(40, 40, 40))
+            };
+            let surface = surface.map_err(|e| e.to_string())?;
+
+            text_quad.set_width(surface.width());
+            text_quad.set_height(surface.height());
+            let texture = creator
+                .create_texture_from_surface(&surface)
+                .map_err(|e| e.to_string())?;
+            self.canvas.copy(&texture, None, text_quad)?;
+
+            text_quad.set_y(text_quad.y + surface.height() as i32);
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn render_quest_log_menu(
+        &mut self,
+        menu: &QuestLogMenu,
+        texture_manager: &mut TextureManager<WindowContext>,
+        font_man: &FontManager,
+    ) -> Result<(), String> {
+        let box_quad = Rect::new(0, 0, PIXELS_X, PIXELS_Y);
+        let creator = self.canvas.texture_creator();
+
+        let texture = texture_manager.load("assets/UI/bag_menu.png")?;
+        self.canvas.copy(&texture, None, box_quad)?;
+
+        // the left panel shows the objectives of the selected quest, ticked off once done
+        let mut info_lines = vec![];
+        if let Some(quest) = menu.quests.get(menu.selected) {
+            for (lines, done) in quest.objectives.iter() {
+                for (idx, line) in lines.iter().enumerate() {
+                    info_lines.push(match (idx, done) {
+                        (0, true) => format!("[x] {}", line),
+                        (0, false) => format!("[ ] {}", line),
+                        _ => format!("    {}", line),
+                    });
+                }
+            }
+        }
+
+        let mut text_quad = Rect::new(6, 6, 0, 0);
+        for line in info_lines.iter() {
+            let surface = font_man
+                .fonts
+                .munro
+                .render(line)
+                .blended(Color::RGB(231, 232, 237))
+                .map_err(|e| e.to_string())?;
+            text_quad.set_width(surface.width());
+            text_quad.set_height(surface.height());
+            let texture = creator
+                .create_texture_from_surface(&surface)
+                .map_err(|e| e.to_string())?;
+            self.canvas.copy(&texture, None, text_quad)?;
+
+            text_quad.set_y(text_quad.y + surface.height() as i32);
+        }
+
+        // the right panel lists the quests, active ones first
+        let lines: Vec<String> = match menu.quests.is_empty() {
+            true => vec!["No quests yet".to_string()],
+            false => menu
+                .quests
+                .iter()
+                .map(|quest| match quest.completed {
+                    true => format!("{} (done)", quest.name),
+                    false => quest.name.clone(),
+                })
+                .collect(),
+        };
+
+        let mut text_quad = Rect::new(122, 10, 0, 0);
+        for (idx, line) in lines.iter().enumerate() {
+            let surface = font_man.fonts.munro.render(line);
+            let surface = if idx == menu.selected && !menu.quests.is_empty() {
+                surface.blended(Color::RGB(0, 183, 235))
+            } else {
+                surface.blended(Color::RGB(40, 40, 40))
+            };
+            let surface = surface.map_err(|e| e.to_string())?;
+
+            text_quad.set_width(surface.width());
+            text_quad.set_height(surface.height());
+            let texture = creator
+                .create_texture_from_surface(&surface)
+                .map_err(|e| e.to_string())?;
+            self.canvas.copy(&texture, None, text_quad)?;
+
+            text_quad.set_y(text_quad.y + surface.height() as i32);
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn render_shop_menu(
+        &mut self,
+        menu: &ShopMenu,
+        world: &World,
+        texture_manager: &mut TextureManager<WindowContext>,
+        font_man: &FontManager,
+    ) -> Result<(), String> {
+        let box_quad = Rect::new(0, 0, PIXELS_X, PIXELS_Y);
+        let creator = self.canvas.texture_creator();
+
+        let texture = texture_manager.load("assets/UI/bag_menu.png")?;
+        self.canvas.copy(&texture, None, box_quad)?;
+
+        let listing = menu.listing(world);
+
+        // the left panel shows the player's money, whether they are buying or selling,
+        // the description of the highlighted item and what happened with the last trade
+        let money = world.get::<&Wallet>(menu.entity).map_or(0, |wallet| wallet.money);
+        let mut info_lines = vec![
+            format!("Money: ${}", money),
+            match menu.mode {
+                ShopMode::Buy => "< Buy >".to_string(),
+                ShopMode::Sell => "< Sell >".to_string(),
+            },
+            String::new(),
+        ];
+        if let Some((item, _)) = listing.get(menu.selected) {
+            info_lines.extend(menu.description(*item).iter().cloned());
+        }
+        if let Some(message) = &menu.message {
+            info_lines.push(String::new());
+            info_lines.push(message.clone());
+        }
+
+        let mut text_quad = Rect::new(6, 6, 0, 0);
+        for line in info_lines.iter() {
+            if !line.is_empty() {
+                let surface = font_man
+                    .fonts
+                    .munro
+                    .render(line)
+                    .blended(Color::RGB(231, 232, 237))
+                    .map_err(|e| e.to_string())?;
+                text_quad.set_width(surface.width());
+                text_quad.set_height(surface.height());
+                let texture = creator
+                    .create_texture_from_surface(&surface)
+                    .map_err(|e| e.to_string())?;
+                self.canvas.copy(&texture, None, text_quad)?;
+            }
+
+            text_quad.set_y(text_quad.y + font_man.fonts.munro.height());
+        }
+
+        // the right panel lists the items and their prices
+        if listing.is_empty() {
+            let surface = font_man
+                .fonts
+                .munro
+                .render("Nothing to sell")
+                .blended(Color::RGB(40, 40, 40))
+                .map_err(|e| e.to_string())?;
+            let texture = creator
+                .create_texture_from_surface(&surface)
+                .map_err(|e| e.to_string())?;
+            self.canvas
+                .copy(&texture, None, Rect::new(122, 10, surface.width(), surface.height()))?;
+        }
+
+        let mut text_quad = Rect::new(122, 10, 0, 0);
+        for (idx, (item, price)) in listing.iter().enumerate() {
+            let color = if idx == menu.selected {
+                Color::RGB(0, 183, 235)
+            } else {
+                Color::RGB(40, 40, 40)
+            };
+            let item_surface = font_man
+                .fonts
+                .munro
+                .render(&item.to_string())
+                .blended(color)
+                .map_err(|e| e.to_string())?;
+            let price_surface = font_man
+                .fonts
+                .munro
+                .render(&format!("${}", price))
+                .blended(color)
+                .map_err(|e| e.to_string())?;
+
+            text_quad.set_width(item_surface.width());
+            text_quad.set_height(item_surface.height());
+            let price_quad = Rect::new(
+                PIXELS_X as i32 - 10 - price_surface.width() as i32,
+                text_quad.y,
+                price_surface.width(),
+                price_surface.height(),
+            );
+
+            let item_texture = creator
+                .create_texture_from_surface(&item_surface)
+                .map_err(|e| e.to_string())?;
+            let price_texture = creator
+                .create_texture_from_surface(&price_surface)
+                .map_err(|e| e.to_string())?;
+            self.canvas.copy(&item_texture, None, text_quad)?;
+            self.canvas.copy(&price_texture, None, price_quad)?;
+
+            text_quad.set_y(text_quad.y + item_surface.height() as i32);
+        }
+
+        Ok(())
+    }
 }
@@ -3,7 +3,7 @@
 // Description: Data and logic needed by other rendering functions
 // including storing all textures and other information needed to render the game properly
 /****************************************************/
-use enum_map::{enum_map, EnumMap};
+use enum_map::EnumMap;
 use hecs::World;
 use sdl2::{
     rect::Rect,
@@ -20,12 +20,14 @@ use crate::{
     tilemap::{FloorTile, WallTile},
 };
 
-use self::overworld::Camera;
+use self::{overworld::Camera, tile_animation::TileAnimations};
 
 mod battle;
+mod error;
 mod menus;
 mod overworld;
 mod resize;
+mod tile_animation;
 
 pub const PIXELS_X: u32 = 240;
 pub const PIXELS_Y: u32 = 160;
@@ -46,98 +48,25 @@ pub struct Renderer {
     camera: Camera,
     floortile_rects: EnumMap<FloorTile, Rect>,
     walltile_rects: EnumMap<WallTile, Rect>,
+    tile_animations: TileAnimations,
 }
 
 impl Renderer {
     //creates a new Renderer given the current canvas
-    pub fn new(canvas: Canvas<Window>) -> Renderer {
+    //returns an error if the tile animations cannot be loaded
+    pub fn new(canvas: Canvas<Window>) -> Result<Renderer, String> {
         let size = TILE_SIZE as u32;
-        let floortile_rects = enum_map! {
-            FloorTile::NONE => Rect::new(0,0, size, size),
-            FloorTile::GRASS1 => Rect::new(32, 0, size, size),
-            FloorTile::GRASS2 => Rect::new(48, 0, size, size),
-            FloorTile::WATER1 => Rect::new(16, 64, size, size),
-            FloorTile::WGTL => Rect::new(0, 48, size, size),
-            FloorTile::WGT => Rect::new(16, 48, size, size),
-            FloorTile::WGTR => Rect::new(32, 48, size, size),
-            FloorTile::WGL => Rect::new(0, 64, size, size),
-            FloorTile::WGR => Rect::new(32, 64, size, size),
-            FloorTile::WGBL => Rect::new(0, 80, size, size),
-            FloorTile::WGB => Rect::new(16, 80, size, size),
-            FloorTile::WGBR => Rect::new(32, 80, size, size),
-            FloorTile::GWTL => Rect::new(48, 48, size, size),
-            FloorTile::GWTR => Rect::new(80, 48, size, size),
-            FloorTile::GWBL => Rect::new(48, 80, size, size),
-            FloorTile::GWBR => Rect::new(80, 80, size, size),
-            FloorTile::SGTL => Rect::new( 96,48, size, size),
-            FloorTile::SGTM => Rect::new( 112,48, size, size),
-            FloorTile::SGTR => Rect::new( 128,48, size, size),
-            FloorTile::SGML => Rect::new( 96,64, size, size),
-            FloorTile::SGC  => Rect::new( 112,64, size, size),
-            FloorTile::SGMR => Rect::new( 128,64, size, size),
-            FloorTile::SGBL => Rect::new( 96,80, size, size),
-            FloorTile::SGBM => Rect::new(112 ,80, size, size),
-            FloorTile::SGBR => Rect::new(128,80, size, size),
-            FloorTile::FB1 => Rect::new(112, 0, size, size),
-            FloorTile::GRASSPATH_V => Rect::new(192,80,size,size),
-            FloorTile::GRASSPATH_AB => Rect::new(112,48,size,size),
-            FloorTile::GRASSPATH_NB => Rect::new(144,48,size,size),
-            FloorTile::GRASSPATH_LB => Rect::new(144,64,size,size),
-            FloorTile::GRASSPATH_TB => Rect::new(160,48,size,size),
-            FloorTile::GRASSPATH_BB => Rect::new(160,80,size,size),
-            FloorTile::GRASSPATH_RB => Rect::new(176,64,size,size),
-            FloorTile::GRASSPATH_LU => Rect::new(176,48,size,size),
-            FloorTile::GRASSPATH_LD => Rect::new(192,48,size,size),
-            FloorTile::GRASSPATH_UR => Rect::new(192,64,size,size),
-            FloorTile::GRASSPATH_DL => Rect::new(144,80,size,size),
-            FloorTile::GRASSPATH_H  => Rect::new(176,80,size,size),
-        };
+        let floortile_rects = EnumMap::from_fn(|tile: FloorTile| {
+            let (x, y) = tile.sprite_pos();
+            Rect::new(x, y, size, size)
+        });
 
-        let walltile_rects = enum_map! {
-            WallTile::NONE => Rect::new(0,0, size, size),
-            WallTile::WOODL => Rect::new(128,0,size,size),
-            WallTile::WOODR => Rect::new(160,0,size,size),
-            WallTile::WOOD => Rect::new(134,0,size,size),
-            WallTile::WINDOW => Rect::new(176,0,size,size),
-            WallTile::FENCE_L => Rect::new(96,16,size,size),
-            WallTile::FENCE_M => Rect::new(112,16,size,size),
-            WallTile::FENCE_R => Rect::new(128,16,size,size),
-            WallTile::FENCE_S => Rect::new(144,16,size,size),
-            WallTile::FENCE_HL => Rect::new(160,16,size,size),
-            WallTile::FENCE_HR => Rect::new(176,16,size,size),
-            WallTile::FENCE_TR => Rect::new(192,16,size,size),
-            WallTile::FENCE_TL => Rect::new(208,16,size,size),
-            WallTile::FENCE_DL => Rect::new(224,16,size,size),
-            WallTile::FENCE_BL => Rect::new(240,16,size,size),
-            WallTile::FENCE_DR => Rect::new(256,16,size,size),
-            WallTile::FENCE_BR => Rect::new(272,16,size,size),
-            WallTile::TGRASS_1 => Rect::new(0,112,size,size),
-            WallTile::TGRASS_2 => Rect::new(16,112,size,size),
-            WallTile::TGRASS_3 => Rect::new(32,112,size,size),
-            WallTile::TGRASS_4 => Rect::new(48,112,size,size),
-            WallTile::TREE_BOTTOM => Rect::new(80,112,size,size),
-            WallTile::TREE_TOP => Rect::new(96,112,size,size),
-            WallTile::TREES => Rect::new(112,112,size,size),
-            WallTile::ROOF_1 => Rect::new(304,0,size,size),
-            WallTile::ROOF_2 => Rect::new(320,0,size,size),
-            WallTile::ROOF_3 => Rect::new(336,0,size,size),
-            WallTile::ROOF_4 => Rect::new(352,0,size,size),
-            WallTile::ROOF_5 => Rect::new(288,16,size,size),
-            WallTile::ROOF_6 => Rect::new(304,16,size,size),
-            WallTile::ROOF_7 => Rect::new(320,16,size,size),
-            WallTile::ROOF_8 => Rect::new(336,16,size,size),
-            WallTile::ROOF_9 => Rect::new(352,16,size,size),
-            WallTile::ROOF_10 => Rect::new(368,16,size,size),
-            WallTile::ROOF_11 => Rect::new(288,32,size,size),
-            WallTile::ROOF_12 => Rect::new(304,32,size,size),
-            WallTile::ROOF_13 => Rect::new(320,32,size,size),
-            WallTile::ROOF_14 => Rect::new(336,32,size,size),
-            WallTile::ROOF_15 => Rect::new(352,32,size,size),
-            WallTile::ROOF_16 => Rect::new(368,32,size,size),
-            WallTile::DOOR => Rect::new(96,0,size,size),
-        };
+        let walltile_rects = EnumMap::from_fn(|tile: WallTile| {
+            let (x, y) = tile.sprite_pos();
+            Rect::new(x, y, size, size)
+        });
 
-        Renderer {
+        Ok(Renderer {
             window_x: PIXELS_X,
             window_y: PIXELS_Y,
             old_window_x: PIXELS_X,
@@ -146,7 +75,8 @@ impl Renderer {
             camera: Camera::default(),
             floortile_rects,
             walltile_rects,
-        }
+            tile_animations: TileAnimations::load()?,
+        })
     }
 
     //runs present on the canvas
@@ -219,12 +149,24 @@ impl Renderer {
                 menu::Menu::Textbox(textbox) => {
                     self.render_textbox(textbox, texture_manager, font_man)?
                 }
+                menu::Menu::ChoiceMenu(menu) => {
+                    self.render_choice_menu(menu, texture_manager, font_man)?
+                }
                 menu::Menu::PauseMenu(menu) => {
                     self.render_pause_menu(menu, texture_manager, font_man)?
                 }
                 menu::Menu::BagMenu(menu) => {
                     self.render_bag_menu(menu, world, texture_manager, font_man)?
                 }
+                menu::Menu::EncyclopediaMenu(menu) => {
+                    self.render_encyclopedia_menu(menu, world, texture_manager, font_man)?
+                }
+                menu::Menu::QuestLogMenu(menu) => {
+                    self.render_quest_log_menu(menu, texture_manager, font_man)?
+                }
+                menu::Menu::ShopMenu(menu) => {
+                    self.render_shop_menu(menu, world, texture_manager, font_man)?
+                }
                 menu::Menu::MovesMenu(menu) => {
                     //if !matches!(menu_man.menus[0], Menu::Textbox(_)) {
                     self.render_moves_menu(menu, texture_manager, font_man)?
@@ -7,17 +7,22 @@ use hecs::World;
 use sdl2::{pixels::Color, rect::Rect, video::WindowContext};
 
 use crate::{
-    components::{animation::HumanWalkAnimation, sprite::Sprite, Player, Position, Collision},
+    components::{
+        animation::HumanWalkAnimation, party::Party, sprite::Sprite, Collision, Follower, MovingEntity,
+        Player, Position, Surfing, WildStray,
+    },
     constants::TILE_SIZE,
     font_manager::FontManager,
     menu,
     resource_manager::TextureManager,
-    tilemap::{self, FloorTile, WallTile, CollisionTile},
-    vec2::Vec2,
+    tilemap::{self, FloorTile, WallTile, CollisionTile, TileLayer},
+    vec2::{Direction, Vec2},
 };
 
 use super::{Renderer, PIXELS_X, PIXELS_Y};
 
+const SURF_RIDER_HEIGHT: i32 = 4; //pixels the player is raised by while riding a water stray
+
 #[derive(Default)]
 pub struct Camera {
     offset: (i32, i32),
@@ -35,11 +40,12 @@ impl Renderer {
         world: &World,
         map: &mut tilemap::TileMap,
         menu_man: &mut menu::MenuManager,
+        camera_focus: Option<Vec2>,
     ) -> Result<(), String> {
         self.canvas.set_draw_color(Color::RGB(39, 45, 46));
         self.canvas.clear();
 
-        self.update_camera(world)?;
+        self.update_camera(world, camera_focus)?;
         self.render_overworld_tiles(texture_manager, map)?;
         self.render_entities(world, texture_manager)?;
         self.render_front_overworld_tiles(texture_manager, map)?;
@@ -48,11 +54,12 @@ impl Renderer {
         Ok(())
     }
 
-    //changes the camera offset to stay focussed on the player
-    //takes in the world, returns ok if no problems
-    pub fn update_camera(&mut self, world: &World) -> Result<(), String> {
+    //changes the camera offset to stay focussed on the player, or on a tile a script has moved the camera to
+    //takes in the world and the tile to focus on instead of the player, returns ok if no problems
+    pub fn update_camera(&mut self, world: &World, focus: Option<Vec2>) -> Result<(), String> {
         let mut q = world.query::<(&Player, &Position, &Sprite)>();
         let (_, (_player, Position(pos), sprite)) = q.iter().next().ok_or("No player found")?;
+        let pos = focus.unwrap_or(*pos);
 
         let offset = (
             (pos.0 * TILE_SIZE as f32).round() as i32
@@ -79,7 +86,22 @@ impl Renderer {
         world: &World,
         texture_manager: &mut TextureManager<WindowContext>,
     ) -> Result<(), String> {
-        let mut entity_query = world.query::<(&Position, &Sprite, Option<&HumanWalkAnimation>)>();
+        let mut entity_query = world.query::<(
+            &Position,
+            &Sprite,
+            Option<&HumanWalkAnimation>,
+            Option<&MovingEntity>,
+            Option<&Surfing>,
+            Option<&Follower>,
+            Option<&WildStray>,
+        )>();
+
+        //the texture of the lead stray, drawn for the stray following the player
+        let lead_texture = world
+            .query::<(&Player, &Party)>()
+            .iter()
+            .next()
+            .and_then(|(_, (_, party))| party.lead().map(|stray| stray.texture.clone()));
 
         let mut list = entity_query
             .iter()
@@ -91,10 +113,29 @@ impl Renderer {
             c1.partial_cmp(c2).unwrap()
         });
 
-        for (_, (Position(Vec2(x, y)), sprite, anim)) in list {
+        for (_, (Position(Vec2(x, y)), sprite, anim, moving, surfing, follower, wild)) in list {
+            let tile_x = (*x * TILE_SIZE as f32).round() as i32 - self.camera.offset.0;
+            let tile_y = (*y * TILE_SIZE as f32).round() as i32 - self.camera.offset.1;
+
+            //the lead stray following the player and wild strays roaming the map are drawn from their battle sprites,
+            //shrunk down to about a tile
+            if follower.is_some() || wild.is_some() {
+                let stray_texture = match (follower, wild) {
+                    (_, Some(wild)) => Some(&wild.stray.texture),
+                    (Some(follower), None) if !follower.hidden => lead_texture.as_ref(),
+                    _ => None,
+                };
+                let Some(stray_texture) = stray_texture else { continue };
+                let facing = anim.map_or(Direction::Down, |anim| anim.rotation);
+                self.render_small_stray(texture_manager, stray_texture, facing, tile_x - 2, tile_y - 4)?;
+                continue;
+            }
+            //entities hopping down a ledge are lifted off the ground, and riders sit on top of their stray
+            let lift = moving.map_or(0, |moving| moving.hop_height())
+                + surfing.map_or(0, |_| SURF_RIDER_HEIGHT);
             let render_quad = Rect::new(
-                (*x * TILE_SIZE as f32).round() as i32 - self.camera.offset.0 + sprite.shift_x,
-                (*y * TILE_SIZE as f32).round() as i32 - self.camera.offset.1 + sprite.shift_y,
+                tile_x + sprite.shift_x,
+                tile_y + sprite.shift_y - lift,
                 sprite.src.width(),
                 sprite.src.height(),
             );
@@ -108,11 +149,41 @@ impl Renderer {
                     self.canvas.copy(&texture, sprite.src, render_quad)?;
                 }
             }
+
+            //the stray being ridden is drawn over the rider's legs, using the side of its battle sprite facing the same way
+            if let Some(surfing) = surfing {
+                let facing = anim.map_or(Direction::Down, |anim| anim.rotation);
+                self.render_small_stray(texture_manager, &surfing.texture, facing, tile_x - 2, tile_y - 2)?;
+            }
         }
 
         Ok(())
     }
 
+    //draws a stray in the overworld from its battle sprite, shrunk down to about a tile
+    //takes in the texture manager, the stray's texture, the direction it faces (picking the side of the sprite
+    //facing the same way) and the top left corner to draw it at on screen, returns ok if no problems
+    fn render_small_stray(
+        &mut self,
+        texture_manager: &mut TextureManager<WindowContext>,
+        stray_texture: &str,
+        facing: Direction,
+        x: i32,
+        y: i32,
+    ) -> Result<(), String> {
+        let frame = match facing {
+            Direction::Left | Direction::Down => 0,
+            Direction::Right | Direction::Up => 1,
+        };
+        let texture = texture_manager.load(stray_texture)?;
+        let stray_size = (TILE_SIZE + 4) as u32;
+        self.canvas.copy(
+            &texture,
+            Rect::new(frame * 64, 0, 64, 64),
+            Rect::new(x, y, stray_size, stray_size),
+        )
+    }
+
     //renders the tiles in the overworld map
     //takes in the texture manager and the map to be rendered, returns ok if no problems
     pub fn render_overworld_tiles(
@@ -129,27 +200,43 @@ impl Renderer {
         let top_left = Vec2::from(self.camera.top_left).to_usize(map.size_x);
         let bottom_right = Vec2::from(self.camera.bottom_right).to_usize(map.size_x);
 
-        for i in top_left..bottom_right {
-            let render_quad = Rect::new(
-                (i % map.size_x) as i32 * TILE_SIZE - self.camera.offset.0,
-                (i / map.size_x) as i32 * TILE_SIZE - self.camera.offset.1,
-                TILE_SIZE as u32,
-                TILE_SIZE as u32,
-            );
+        for layer in map.layers.iter() {
+            for i in top_left..bottom_right {
+                let render_quad = Rect::new(
+                    (i % map.size_x) as i32 * TILE_SIZE - self.camera.offset.0,
+                    (i / map.size_x) as i32 * TILE_SIZE - self.camera.offset.1,
+                    TILE_SIZE as u32,
+                    TILE_SIZE as u32,
+                );
 
-            if let Some(tile) = map.floor.get(i) {
-                if !matches!(tile, FloorTile::NONE) {
-                    let src = self.floortile_rects[*tile];
-                    self.canvas.copy(&texture, src, render_quad)?
-                }
-            };
-            if let Some(tile) = map.walls.get(i) {
-                // check if the tile is empty AND if it is not a front tile (to be rendered after the entities)
-                if !matches!(tile, WallTile::NONE) && !map.front_filter.contains(tile) {
-                    let src = self.walltile_rects[*tile];
-                    self.canvas.copy(&texture, src, render_quad)?
+                match layer {
+                    TileLayer::Floor(tiles) => {
+                        if let Some(tile) = tiles.get(i) {
+                            if !matches!(tile, FloorTile::NONE) {
+                                let src = self
+                                    .tile_animations
+                                    .floor_src(*tile, map.animation_time)
+                                    .unwrap_or(self.floortile_rects[*tile]);
+                                self.canvas.copy(&texture, src, render_quad)?
+                            }
+                        };
+                    }
+                    TileLayer::Wall(tiles) => {
+                        if let Some(tile) = tiles.get(i) {
+                            // check if the tile is empty AND if it is not a front tile (to be rendered after the entities)
+                            if !matches!(tile, WallTile::NONE) && !map.is_above_player(*tile) {
+                                let src = self
+                                    .tile_animations
+                                    .wall_src(*tile, map.animation_time)
+                                    .unwrap_or(self.walltile_rects[*tile]);
+                                self.canvas.copy(&texture, src, render_quad)?
+                            }
+                        };
+                    }
+                    // front layers are rendered after the entities
+                    TileLayer::Front(_) => {}
                 }
-            };
+            }
         }
 
         Ok(())
@@ -169,21 +256,31 @@ impl Renderer {
         let top_left = Vec2::from(self.camera.top_left).to_usize(map.size_x);
         let bottom_right = Vec2::from(self.camera.bottom_right).to_usize(map.size_x);
 
-        for i in top_left..bottom_right {
-            let render_quad = Rect::new(
-                (i % map.size_x) as i32 * TILE_SIZE - self.camera.offset.0,
-                (i / map.size_x) as i32 * TILE_SIZE - self.camera.offset.1,
-                TILE_SIZE as u32,
-                TILE_SIZE as u32,
-            );
+        for layer in map.layers.iter() {
+            for i in top_left..bottom_right {
+                let render_quad = Rect::new(
+                    (i % map.size_x) as i32 * TILE_SIZE - self.camera.offset.0,
+                    (i / map.size_x) as i32 * TILE_SIZE - self.camera.offset.1,
+                    TILE_SIZE as u32,
+                    TILE_SIZE as u32,
+                );
 
-            // render wall tiles in the front_filter after entities are rendered
-            if let Some(tile) = map.walls.get(i) {
-                if map.front_filter.contains(tile) {
-                    let src = self.walltile_rects[*tile];
-                    self.canvas.copy(&texture, src, render_quad)?
-                }
-            };
+                // render wall tiles drawn above the player and front layers after entities are rendered
+                let tile = match layer {
+                    TileLayer::Wall(tiles) => tiles.get(i).filter(|tile| map.is_above_player(**tile)),
+                    TileLayer::Front(tiles) => tiles.get(i),
+                    TileLayer::Floor(_) => None,
+                };
+                if let Some(tile) = tile {
+                    if !matches!(tile, WallTile::NONE) {
+                        let src = self
+                            .tile_animations
+                            .wall_src(*tile, map.animation_time)
+                            .unwrap_or(self.walltile_rects[*tile]);
+                        self.canvas.copy(&texture, src, render_quad)?
+                    }
+                };
+            }
         }
 
         Ok(())
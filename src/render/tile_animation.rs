@@ -0,0 +1,90 @@
+/****************************************************/
+// Description: Animations for overworld tiles, loaded from assets/tiles/animations.json
+// each animated tile lists the frames it cycles through in tilesprites.png and how long each frame is shown
+/****************************************************/
+use std::fs;
+
+use enum_map::EnumMap;
+use sdl2::rect::Rect;
+use serde::Deserialize;
+
+use crate::{
+    constants::TILE_SIZE,
+    tilemap::{FloorTile, WallTile},
+};
+
+pub const ANIMATIONS_PATH: &str = "assets/tiles/animations.json";
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AnimationFrame {
+    pub x: i32,
+    pub y: i32,
+    pub duration: f32, //time in ms the frame is shown for
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct TileAnimation<T> {
+    tile: T,
+    frames: Vec<AnimationFrame>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct AnimationFile {
+    #[serde(default)]
+    floor: Vec<TileAnimation<FloorTile>>,
+    #[serde(default)]
+    wall: Vec<TileAnimation<WallTile>>,
+}
+
+#[derive(Default)]
+pub struct TileAnimations {
+    floor: EnumMap<FloorTile, Vec<AnimationFrame>>,
+    wall: EnumMap<WallTile, Vec<AnimationFrame>>,
+}
+
+//picks the frame shown after some time, looping through the frames
+fn frame_at(frames: &[AnimationFrame], time: f32) -> Option<&AnimationFrame> {
+    let total: f32 = frames.iter().map(|frame| frame.duration).sum();
+    if total <= 0.0 {
+        return frames.first();
+    }
+
+    let mut time = time % total;
+    frames.iter().find(|frame| {
+        time -= frame.duration;
+        time < 0.0
+    })
+}
+
+impl TileAnimations {
+    //loads the tile animations from their data file
+    pub fn load() -> Result<Self, String> {
+        let text = fs::read_to_string(ANIMATIONS_PATH)
+            .map_err(|e| format!("{}: {}", ANIMATIONS_PATH, e))?;
+        let file: AnimationFile =
+            serde_json::from_str(&text).map_err(|e| format!("{}: {}", ANIMATIONS_PATH, e))?;
+
+        let mut animations = Self::default();
+        for animation in file.floor {
+            animations.floor[animation.tile] = animation.frames;
+        }
+        for animation in file.wall {
+            animations.wall[animation.tile] = animation.frames;
+        }
+        Ok(animations)
+    }
+
+    //returns the source rect of a floor tile after some time, if the tile is animated
+    pub fn floor_src(&self, tile: FloorTile, time: f32) -> Option<Rect> {
+        frame_at(&self.floor[tile], time).map(frame_rect)
+    }
+
+    //returns the source rect of a wall tile after some time, if the tile is animated
+    pub fn wall_src(&self, tile: WallTile, time: f32) -> Option<Rect> {
+        frame_at(&self.wall[tile], time).map(frame_rect)
+    }
+}
+
+fn frame_rect(frame: &AnimationFrame) -> Rect {
+    Rect::new(frame.x, frame.y, TILE_SIZE as u32, TILE_SIZE as u32)
+}
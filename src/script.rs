@@ -0,0 +1,215 @@
+/****************************************************/
+// Description: Data structures for event scripts, the sequences of steps run for cutscenes and story events
+// each script is a json file in scripts/ that is started by talking to an npc or stepping on a map trigger
+// and the conditions that scripts, npc spawns, warps and quests check against the player's progress
+/****************************************************/
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{
+        bag::{Bag, Item},
+        flags::Flags,
+        CharmKind,
+    },
+    vec2::Direction,
+};
+
+pub const SCRIPTS_PATH: &str = "scripts"; //folder holding every script, scripts/<name>.json
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Script {
+    pub steps: Vec<ScriptStep>, //run one after another, each waiting for the last to finish
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScriptStep {
+    //shows a textbox, waiting until it is closed
+    Say {
+        #[serde(default)]
+        speaker: Option<String>, //name shown above the textbox
+        text: Pages,
+    },
+    //asks the player to pick one of the options, then runs the steps of the one picked
+    Choice {
+        #[serde(default)]
+        speaker: Option<String>,
+        text: String,
+        options: Vec<ChoiceOption>,
+    },
+    //runs one list of steps or the other, depending on a condition
+    If {
+        condition: Condition,
+        #[serde(default)]
+        then: Vec<ScriptStep>,
+        #[serde(default, rename = "else")]
+        otherwise: Vec<ScriptStep>,
+    },
+    //walks an npc (found by its id) to a tile
+    MoveNpc {
+        npc: String,
+        x: i32,
+        y: i32,
+        #[serde(default = "default_true")]
+        wait: bool, //waits for the npc to get there, otherwise the script carries on while it walks
+    },
+    //turns an npc to face a direction
+    FaceNpc {
+        npc: String,
+        facing: Direction,
+    },
+    //pans the camera over to a tile, waiting until it gets there
+    Camera {
+        x: i32,
+        y: i32,
+    },
+    //pans the camera back to the player, who it follows again from then on
+    CameraReset,
+    //waits for a time in ms
+    Wait {
+        ms: f32,
+    },
+    //puts items in the player's bag
+    GiveItem {
+        item: Item,
+        #[serde(default = "default_amount")]
+        amount: u32,
+    },
+    //takes items out of the player's bag, as many as they have if it is less than the amount
+    TakeItem {
+        item: Item,
+        #[serde(default = "default_amount")]
+        amount: u32,
+    },
+    //restores the health of every stray in the party
+    Heal,
+    //upgrades the player's totem if they have the materials, saying what happened and waiting until it is read
+    UpgradeTotem,
+    //gives the player a spirit charm, which lasts for a number of steps or a time in ms
+    //(CHARM_STEPS steps if neither is given) and replaces any charm they already had
+    Charm {
+        charm: CharmKind,
+        #[serde(default)]
+        steps: Option<u32>,
+        #[serde(default)]
+        ms: Option<f32>,
+    },
+    //gives the first stray of a species in the party a nickname, or takes its nickname away if the name is empty
+    Nickname {
+        species: String,
+        name: String,
+    },
+    //starts a battle against strays of the given species, waiting until it is over
+    Battle {
+        strays: Vec<String>,
+        #[serde(default)]
+        reward: u32, //money the player gets for winning
+    },
+    //moves the player to a tile on another map (or the same one), waiting until the screen fades back in
+    Warp {
+        map: i32,
+        x: i32,
+        y: i32,
+        #[serde(default)]
+        facing: Option<Direction>,
+    },
+    //sets or clears a story flag
+    SetFlag {
+        flag: String,
+    },
+    ClearFlag {
+        flag: String,
+    },
+    //changes a story counter, such as how many times the player has done something
+    AddCounter {
+        counter: String,
+        #[serde(default = "default_counter_amount")]
+        amount: i32,
+    },
+    SetCounter {
+        counter: String,
+        value: i32,
+    },
+}
+
+//the text of a textbox, either one page or a list of pages each starting on a new textbox
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Pages {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Pages {
+    //returns every page of the text
+    pub fn pages(&self) -> Vec<String> {
+        match self {
+            Pages::One(page) => vec![page.clone()],
+            Pages::Many(pages) => pages.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChoiceOption {
+    pub text: String,
+    #[serde(default)]
+    pub condition: Option<Condition>, //the option is only offered if this holds
+    #[serde(default)]
+    pub steps: Vec<ScriptStep>, //run when the option is picked
+}
+
+//something about the player's progress a script can check, every part that is given has to hold
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Condition {
+    pub flag: Option<String>,     //story flag that has to be set
+    pub not_flag: Option<String>, //story flag that can't be set
+    pub item: Option<Item>,       //item the player has to carry
+    pub amount: Option<u32>,      //how many of the item, 1 if not given
+    pub counter: Option<String>,  //story counter to compare
+    pub at_least: Option<i32>,    //lowest value the counter can have, 1 if neither limit is given
+    pub below: Option<i32>,       //value the counter has to stay under
+}
+
+impl Condition {
+    //checks the condition against the player's story flags and bag
+    pub fn check(&self, flags: &Flags, bag: &Bag) -> bool {
+        let flag = self.flag.as_ref().is_none_or(|flag| flags.is_set(flag));
+        let not_flag = self.not_flag.as_ref().is_none_or(|flag| !flags.is_set(flag));
+        let item = self.item.is_none_or(|item| bag.count(item) >= self.amount.unwrap_or(1));
+        let counter = self.counter.as_ref().is_none_or(|counter| {
+            let value = flags.counter(counter);
+            let at_least = match (self.at_least, self.below) {
+                (None, None) => Some(1),
+                (at_least, _) => at_least,
+            };
+            at_least.is_none_or(|min| value >= min) && self.below.is_none_or(|max| value < max)
+        });
+        flag && not_flag && item && counter
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_amount() -> u32 {
+    1
+}
+
+fn default_counter_amount() -> i32 {
+    1
+}
+
+impl Script {
+    //loads a script by name from the scripts folder
+    //returns the script, or a message saying why it could not be read
+    pub fn load(name: &str) -> Result<Script, String> {
+        let path = format!("{}/{}.json", SCRIPTS_PATH, name);
+        let text = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+        serde_json::from_str(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+}
@@ -5,12 +5,19 @@
 /****************************************************/
 use enum_map::Enum;
 use num_derive::FromPrimitive;
+use serde::Deserialize;
 use num_traits::FromPrimitive;
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
-use crate::vec2::{Vec2, self};
+use crate::{
+    components::stray::SPECIES,
+    vec2::{self, Vec2},
+};
 
-#[derive(FromPrimitive, ToPrimitive, Debug, Enum, Clone, Copy)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Enum, Clone, Copy, Deserialize)]
 pub enum FloorTile {
     NONE,
     GRASS1,
@@ -50,9 +57,33 @@ pub enum FloorTile {
     GRASSPATH_UR,
     GRASSPATH_DL,
     GRASSPATH_H,
+    PGTL,
+    PGT,
+    PGTR,
+    PGL,
+    PATH,
+    PGR,
+    PGBL,
+    PGB,
+    PGBR,
+    GPTL,
+    GPT,
+    GPTR,
+    GPL,
+    GRASSFLAT,
+    GPR,
+    GPBL,
+    GPB,
+    GPBR,
+    ICE,
+    SNOW,
+    SPIN_UP,
+    SPIN_DOWN,
+    SPIN_LEFT,
+    SPIN_RIGHT,
 }
 
-#[derive(FromPrimitive, ToPrimitive, PartialEq, Debug, Enum, Clone, Copy)]
+#[derive(FromPrimitive, ToPrimitive, PartialEq, Debug, Enum, Clone, Copy, Deserialize)]
 pub enum WallTile {
     NONE,
     WOODL,
@@ -95,6 +126,11 @@ pub enum WallTile {
     ROOF_15,
     ROOF_16,
     DOOR,
+    FLOWERS,
+    LEDGE_DOWN,
+    LEDGE_LEFT,
+    LEDGE_RIGHT,
+    THORNS,
 }
 
 #[derive(FromPrimitive, ToPrimitive, PartialEq, Debug)]
@@ -104,140 +140,457 @@ pub enum CollisionTile {
     Door,
 }
 
+impl FloorTile {
+    //returns the position of the tile's sprite in tilesprites.png
+    pub fn sprite_pos(self) -> (i32, i32) {
+        match self {
+            FloorTile::NONE => (0, 0),
+            FloorTile::GRASS1 => (32, 0),
+            FloorTile::GRASS2 => (48, 0),
+            FloorTile::WATER1 => (16, 64),
+            FloorTile::WGTL => (0, 48),
+            FloorTile::WGT => (16, 48),
+            FloorTile::WGTR => (32, 48),
+            FloorTile::WGL => (0, 64),
+            FloorTile::WGR => (32, 64),
+            FloorTile::WGBL => (0, 80),
+            FloorTile::WGB => (16, 80),
+            FloorTile::WGBR => (32, 80),
+            FloorTile::GWTL => (48, 48),
+            FloorTile::GWTR => (80, 48),
+            FloorTile::GWBL => (48, 80),
+            FloorTile::GWBR => (80, 80),
+            FloorTile::SGTL => (96, 48),
+            FloorTile::SGTM => (112, 48),
+            FloorTile::SGTR => (128, 48),
+            FloorTile::SGML => (96, 64),
+            FloorTile::SGC => (112, 64),
+            FloorTile::SGMR => (128, 64),
+            FloorTile::SGBL => (96, 80),
+            FloorTile::SGBM => (112, 80),
+            FloorTile::SGBR => (128, 80),
+            FloorTile::FB1 => (112, 0),
+            FloorTile::GRASSPATH_V => (192, 80),
+            FloorTile::GRASSPATH_AB => (112, 48),
+            FloorTile::GRASSPATH_NB => (144, 48),
+            FloorTile::GRASSPATH_LB => (144, 64),
+            FloorTile::GRASSPATH_TB => (160, 48),
+            FloorTile::GRASSPATH_BB => (160, 80),
+            FloorTile::GRASSPATH_RB => (176, 64),
+            FloorTile::GRASSPATH_LU => (176, 48),
+            FloorTile::GRASSPATH_LD => (192, 48),
+            FloorTile::GRASSPATH_UR => (192, 64),
+            FloorTile::GRASSPATH_DL => (144, 80),
+            FloorTile::GRASSPATH_H => (176, 80),
+            FloorTile::PGTL => (208, 48),
+            FloorTile::PGT => (224, 48),
+            FloorTile::PGTR => (240, 48),
+            FloorTile::PGL => (208, 64),
+            FloorTile::PATH => (224, 64),
+            FloorTile::PGR => (240, 64),
+            FloorTile::PGBL => (208, 80),
+            FloorTile::PGB => (224, 80),
+            FloorTile::PGBR => (240, 80),
+            FloorTile::GPTL => (256, 48),
+            FloorTile::GPT => (272, 48),
+            FloorTile::GPTR => (288, 48),
+            FloorTile::GPL => (256, 64),
+            FloorTile::GRASSFLAT => (272, 64),
+            FloorTile::GPR => (288, 64),
+            FloorTile::GPBL => (256, 80),
+            FloorTile::GPB => (272, 80),
+            FloorTile::GPBR => (288, 80),
+            FloorTile::ICE => (0, 176),
+            FloorTile::SNOW => (16, 176),
+            FloorTile::SPIN_UP => (32, 176),
+            FloorTile::SPIN_DOWN => (48, 176),
+            FloorTile::SPIN_LEFT => (64, 176),
+            FloorTile::SPIN_RIGHT => (80, 176),
+        }
+    }
+}
+
+impl WallTile {
+    //returns the position of the tile's sprite in tilesprites.png
+    pub fn sprite_pos(self) -> (i32, i32) {
+        match self {
+            WallTile::NONE => (0, 0),
+            WallTile::WOODL => (128, 0),
+            WallTile::WOODR => (160, 0),
+            WallTile::WOOD => (134, 0),
+            WallTile::WINDOW => (176, 0),
+            WallTile::FENCE_L => (96, 16),
+            WallTile::FENCE_M => (112, 16),
+            WallTile::FENCE_R => (128, 16),
+            WallTile::FENCE_S => (144, 16),
+            WallTile::FENCE_HL => (160, 16),
+            WallTile::FENCE_HR => (176, 16),
+            WallTile::FENCE_TR => (192, 16),
+            WallTile::FENCE_TL => (208, 16),
+            WallTile::FENCE_DL => (224, 16),
+            WallTile::FENCE_BL => (240, 16),
+            WallTile::FENCE_DR => (256, 16),
+            WallTile::FENCE_BR => (272, 16),
+            WallTile::TGRASS_1 => (0, 112),
+            WallTile::TGRASS_2 => (16, 112),
+            WallTile::TGRASS_3 => (32, 112),
+            WallTile::TGRASS_4 => (48, 112),
+            WallTile::TREE_BOTTOM => (80, 112),
+            WallTile::TREE_TOP => (96, 112),
+            WallTile::TREES => (112, 112),
+            WallTile::ROOF_1 => (304, 0),
+            WallTile::ROOF_2 => (320, 0),
+            WallTile::ROOF_3 => (336, 0),
+            WallTile::ROOF_4 => (352, 0),
+            WallTile::ROOF_5 => (288, 16),
+            WallTile::ROOF_6 => (304, 16),
+            WallTile::ROOF_7 => (320, 16),
+            WallTile::ROOF_8 => (336, 16),
+            WallTile::ROOF_9 => (352, 16),
+            WallTile::ROOF_10 => (368, 16),
+            WallTile::ROOF_11 => (288, 32),
+            WallTile::ROOF_12 => (304, 32),
+            WallTile::ROOF_13 => (320, 32),
+            WallTile::ROOF_14 => (336, 32),
+            WallTile::ROOF_15 => (352, 32),
+            WallTile::ROOF_16 => (368, 32),
+            WallTile::DOOR => (96, 0),
+            WallTile::FLOWERS => (64, 112),
+            WallTile::LEDGE_DOWN => (96, 176),
+            WallTile::LEDGE_LEFT => (112, 176),
+            WallTile::LEDGE_RIGHT => (128, 176),
+            WallTile::THORNS => (144, 176),
+        }
+    }
+}
+
+pub mod autotile;
+pub mod editor;
+pub mod error;
+pub mod format;
+pub mod legacy;
+pub mod properties;
+pub mod tiled;
+
+use self::error::{MapError, MapLoadError};
+use self::format::{EncounterZone, EntitySpawn, LayerKind, MapFile, Trigger, Warp, WarpTrigger};
+use self::properties::{TileProperties, TilePropertyTable, PROPERTIES_PATH};
+
+pub enum TileLayer {
+    Floor(Vec<FloorTile>),
+    Wall(Vec<WallTile>),
+    Front(Vec<WallTile>),
+}
+
 pub struct TileMap {
     pub size_x: usize,
     pub size_y: usize,
-    pub floor: Vec<FloorTile>,
-    pub walls: Vec<WallTile>,
-    pub front_filter: Vec<WallTile>,
+    pub name: String,
+    pub music: Option<String>,
+    pub indoors: bool,
+    pub spawn: Option<(i32, i32)>,
+    pub layers: Vec<TileLayer>,
+    pub properties: &'static TilePropertyTable, //properties of every kind of tile, such as which tiles cause encounters
     pub collision: Vec<CollisionTile>,
+    pub encounter_zones: Vec<EncounterZone>,
+    pub warps: Vec<Warp>,
+    pub entities: Vec<EntitySpawn>,
+    pub triggers: Vec<Trigger>,
     pub id: i32,
+    pub animation_time: f32, //time in ms spent on the map, which picks the frame of animated tiles
 }
 
-impl TileMap {
-    pub fn load(id: i32) -> TileMap {
-        let str = String::from("maps/map") + &id.to_string() + "/";
-        let mapfolder = Path::new(str.as_str());
-        println!("{}", mapfolder.to_str().unwrap());
-
-        //load dimensions of tilemap
-        let dim: Vec<usize> = fs::read_to_string(mapfolder.join("dim.txt"))
-            .unwrap_or_else(|_| panic!("{}dim.txt not found", mapfolder.to_str().unwrap()))
-            .split_whitespace()
-            .map(|x| x.parse::<usize>().expect("Not an integer!"))
-            .collect();
+//parses the rows of a layer into a list of tiles
+//takes in the name of the layer, the rows, the dimensions of the map, and the list every problem found is added to
+//returns the tiles that could be parsed, which are only complete if no problems were found
+fn parse_rows<T: FromPrimitive>(
+    layer: &str,
+    rows: &[String],
+    size_x: usize,
+    size_y: usize,
+    errors: &mut Vec<MapError>,
+) -> Vec<T> {
+    if rows.len() != size_y {
+        errors.push(MapError::RowCount {
+            layer: layer.to_string(),
+            expected: size_y,
+            found: rows.len(),
+        });
+    }
 
-        if dim.len() != 2 {
-            panic!(
-                "{}dim.txt does not contain exactly 2 values",
-                mapfolder.to_str().unwrap()
-            )
+    let mut tiles = Vec::with_capacity(size_x * size_y);
+    for (row, text) in rows.iter().enumerate() {
+        let ids: Vec<&str> = text.split_whitespace().collect();
+        if ids.len() != size_x {
+            errors.push(MapError::RowLength {
+                layer: layer.to_string(),
+                row,
+                expected: size_x,
+                found: ids.len(),
+            });
         }
-        let size_x = dim.get(0).unwrap();
-        let size_y = dim.get(1).unwrap();
-
-        //load floors
-        let floor: Vec<FloorTile> = fs::read_to_string(mapfolder.join("floor.txt"))
-            .unwrap_or_else(|_| panic!("{}floor.txt not found", mapfolder.to_str().unwrap()))
-            .split_whitespace()
-            .map(|x| {
-                FromPrimitive::from_u32(x.parse::<u32>().expect("Not an integer!"))
-                    .expect("Invalid floor tile")
-            })
-            .collect();
 
-        if floor.len() != (size_x * size_y) {
-            panic!(
-                "{}floor.txt does not contain {} values",
-                mapfolder.to_str().unwrap(),
-                size_x * size_y,
-            )
+        for (column, id) in ids.into_iter().enumerate() {
+            match id.parse::<u32>().ok().and_then(FromPrimitive::from_u32) {
+                Some(tile) => tiles.push(tile),
+                None => errors.push(MapError::InvalidTile {
+                    layer: layer.to_string(),
+                    row,
+                    column,
+                    value: id.to_string(),
+                    position: None,
+                }),
+            }
         }
+    }
+
+    tiles
+}
 
-        //load walls
-        let walls: Vec<WallTile> = fs::read_to_string(mapfolder.join("walls.txt"))
-            .unwrap_or_else(|_| panic!("{}walls.txt not found", mapfolder.to_str().unwrap()))
-            .split_whitespace()
-            .map(|x| {
-                FromPrimitive::from_u32(x.parse::<u32>().expect("Not an integer!"))
-                    .expect("Invalid wall tile")
+//finds where the invalid tile ids of a json map are in its text, so they are reported with their line and column
+//ids are matched to their layer by name, and checked against the text in case two layers share a name
+fn locate_invalid_tiles(path: &Path, file: &MapFile, errors: &mut [MapError]) {
+    if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+        return;
+    }
+    let Ok(text) = fs::read_to_string(path) else { return };
+    let rows = error::json_rows(&text);
+
+    for invalid in errors.iter_mut() {
+        let MapError::InvalidTile { layer, row, column, value, position } = invalid else {
+            continue;
+        };
+        let layers = file
+            .layers
+            .iter()
+            .enumerate()
+            .filter(|(_, data)| data.name == *layer)
+            .map(|(i, _)| Some(i));
+        let collision = (layer == "collision").then_some(None);
+
+        *position = layers
+            .chain(collision)
+            .filter_map(|index| rows.get(&(index, *row)))
+            .find_map(|range| {
+                let (offset, id) = error::tokens(&text[range.clone()]).nth(*column)?;
+                (id == value).then_some(range.start + offset)
             })
-            .collect();
+            .map(|offset| error::text_position(&text, offset));
+    }
+}
 
-        if walls.len() != (size_x * size_y) {
-            panic!(
-                "{}walls.txt does not contain {} values",
-                mapfolder.to_str().unwrap(),
-                size_x * size_y,
-            )
+impl TileMap {
+    //returns every file a map is stored in, in the order they are preferred: json, then a zip exported by
+    //the map editor, then a Tiled map
+    //a map should only have one, the game loads the first and lint_maps reports the others
+    pub fn paths(id: i32) -> Vec<PathBuf> {
+        ["json", "zip", "tmx"]
+            .iter()
+            .map(|ext| PathBuf::from(format!("maps/map{}.{}", id, ext)))
+            .filter(|path| path.exists())
+            .collect()
+    }
+
+    //returns the path of the file a map is stored in, which is a json file if the map has no file yet
+    pub fn path(id: i32) -> PathBuf {
+        Self::paths(id)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| PathBuf::from(format!("maps/map{}.json", id)))
+    }
+
+    //reads a map file of any supported format
+    //returns its contents, or every problem found while reading it
+    pub fn read_file(path: &Path) -> Result<MapFile, MapLoadError> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("zip") => return editor::read_zip(path),
+            Some("tmx") => return tiled::read_tmx(path),
+            _ => {}
         }
 
-        //load collision map
-        // filter out wall tiles that you cannot walk behind (only front wall tiles)
-        let front_filter = vec![
-            WallTile::ROOF_1,
-            WallTile::ROOF_2,
-            WallTile::ROOF_3,
-            WallTile::ROOF_4,
-            WallTile::ROOF_5,
-            WallTile::ROOF_10,
-            WallTile::ROOF_11,
-            WallTile::ROOF_16,
-            WallTile::TREE_TOP
-            ];
-
-        let collision: Vec<CollisionTile> = fs::read_to_string(mapfolder.join("collision.txt"))
-            .unwrap_or_else(|_| panic!("{}collision.txt not found", mapfolder.to_str().unwrap()))
-            .split_whitespace()
-            .map(|x| {
-                FromPrimitive::from_u32(x.parse::<u32>().expect("Not an integer!"))
-                    .expect("Invalid collision tile")
+        let text = fs::read_to_string(path).map_err(|e| {
+            MapLoadError::single(path.to_path_buf(), MapError::Io { message: e.to_string() })
+        })?;
+        serde_json::from_str(&text)
+            .map_err(|e| MapLoadError::single(path.to_path_buf(), MapError::from(&e)))
+    }
+
+    //loads a map from its file
+    //returns the map, or every problem found in the file
+    pub fn load(id: i32) -> Result<TileMap, MapLoadError> {
+        let path = Self::path(id);
+        //a map stored in more than one file only loads the first, which is easy to miss
+        for other in Self::paths(id).iter().skip(1) {
+            eprintln!("{}: ignored, map {} is loaded from {}", other.display(), id, path.display());
+        }
+        let file = Self::read_file(&path)?;
+        Self::from_file(id, file, &path)
+    }
+
+    //builds a map from the contents of a map file
+    //takes in the id of the map, the parsed file, and the path it was read from (for error messages)
+    //returns the map, or every problem found in the file
+    pub fn from_file(id: i32, file: MapFile, path: &Path) -> Result<TileMap, MapLoadError> {
+        let (size_x, size_y) = (file.width, file.height);
+        let mut errors = vec![];
+
+        //load tile layers
+        let layers = file
+            .layers
+            .iter()
+            .map(|layer| {
+                let (name, rows) = (&layer.name, &layer.tiles);
+                match layer.kind {
+                    LayerKind::Floor => {
+                        TileLayer::Floor(parse_rows(name, rows, size_x, size_y, &mut errors))
+                    }
+                    LayerKind::Wall => {
+                        TileLayer::Wall(parse_rows(name, rows, size_x, size_y, &mut errors))
+                    }
+                    LayerKind::Front => {
+                        TileLayer::Front(parse_rows(name, rows, size_x, size_y, &mut errors))
+                    }
+                    LayerKind::Terrain => {
+                        let terrain = parse_rows(name, rows, size_x, size_y, &mut errors);
+                        TileLayer::Floor(autotile::autotile(&terrain, size_x, size_y))
+                    }
+                }
             })
             .collect();
-        if collision.len() != (size_x * size_y) {
-            panic!(
-                "{}collision.txt does not contain {} values",
-                mapfolder.to_str().unwrap(),
-                size_x * size_y
-            )
+
+        //load collision map
+        let collision = parse_rows("collision", &file.collision, size_x, size_y, &mut errors);
+
+        //every stray that can be encountered has to exist
+        for (zone, encounter_zone) in file.encounter_zones.iter().enumerate() {
+            for slot in encounter_zone.strays.iter() {
+                if !SPECIES.iter().any(|info| info.name == slot.species) {
+                    errors.push(MapError::UnknownSpecies {
+                        zone,
+                        species: slot.species.clone(),
+                    });
+                }
+            }
         }
 
-        TileMap {
-            size_x: *size_x,
-            size_y: *size_y,
-            floor,
-            walls,
-            front_filter,
+        //the tile properties are the same for every map, so they are only read from disk once
+        let properties = match TilePropertyTable::shared() {
+            Ok(properties) if errors.is_empty() => properties,
+            result => {
+                if let Err(message) = result {
+                    errors.push(MapError::Invalid {
+                        location: PROPERTIES_PATH.to_string(),
+                        message,
+                    });
+                }
+                locate_invalid_tiles(path, &file, &mut errors);
+                return Err(MapLoadError {
+                    file: path.to_path_buf(),
+                    errors,
+                });
+            }
+        };
+
+        Ok(TileMap {
+            size_x,
+            size_y,
+            name: file.name,
+            music: file.music,
+            indoors: file.indoors,
+            spawn: file.spawn,
+            layers,
+            properties,
             collision,
+            encounter_zones: file.encounter_zones,
+            warps: file.warps,
+            entities: file.entities,
+            triggers: file.triggers,
             id,
-        }
+            animation_time: 0.0,
+        })
+    }
+
+    //returns every wall tile (including front tiles) on a tile of the map
+    pub fn walls_at(&self, pos: Vec2) -> impl Iterator<Item = &WallTile> {
+        let i = pos.to_usize(self.size_x);
+        self.layers.iter().filter_map(move |layer| match layer {
+            TileLayer::Wall(tiles) | TileLayer::Front(tiles) => tiles.get(i),
+            TileLayer::Floor(_) => None,
+        })
+    }
+
+    //returns the combined properties of every tile on a spot of the map
+    pub fn properties_at(&self, pos: Vec2) -> TileProperties {
+        let i = pos.to_usize(self.size_x);
+        TileProperties::combine(self.layers.iter().filter_map(|layer| match layer {
+            TileLayer::Floor(tiles) => tiles.get(i).map(|&tile| &self.properties.floor[tile]),
+            TileLayer::Wall(tiles) | TileLayer::Front(tiles) => {
+                tiles.get(i).map(|&tile| &self.properties.wall[tile])
+            }
+        }))
     }
 
-    //checks collision on the map collisions
+    //checks if a wall tile is drawn above entities
+    pub fn is_above_player(&self, tile: WallTile) -> bool {
+        self.properties.wall[tile].above_player
+    }
+
+    //returns the warp on a tile that is activated by the given trigger, if any
+    pub fn warp_at(&self, pos: Vec2, trigger: WarpTrigger) -> Option<&Warp> {
+        let Vec2(x, y) = pos.round_to_tile();
+        self.warps
+            .iter()
+            .find(|warp| warp.trigger == trigger && (warp.x, warp.y) == (x as i32, y as i32))
+    }
+
+    //returns the event script trigger covering a tile, if any
+    pub fn trigger_at(&self, pos: Vec2) -> Option<&Trigger> {
+        self.triggers.iter().find(|trigger| trigger.contains(pos))
+    }
+
+    //returns the encounter zone for the kind of encounters a tile causes, if any
+    pub fn encounter_zone(&self, pos: Vec2) -> Option<&EncounterZone> {
+        let kind = self.encounter_kind(pos)?;
+        self.encounter_zones
+            .iter()
+            .find(|zone| zone.kind == kind && zone.contains(pos))
+    }
+
+    //finds the encounter zone covering a tile whatever kind of tiles it has, for encounters away from where strays hide
+    pub fn encounter_zone_around(&self, pos: Vec2) -> Option<&EncounterZone> {
+        self.encounter_zones.iter().find(|zone| zone.contains(pos))
+    }
+
+    //checks collision on the map collisions and the tiles that cannot be walked on
+    //door tiles can be walked onto so that the player can step through them
     pub fn check_collision(&self, pos: Vec2) -> bool {
-        !matches!(
-            self.collision.get(pos.to_usize(self.size_x)),
-            Some(CollisionTile::None)
-        ) || pos.0 < 0.0
+        pos.0 < 0.0
             || pos.1 < 0.0
             || pos.0 >= self.size_x as f32
             || pos.1 >= self.size_y as f32
+            || !matches!(
+                self.collision.get(pos.to_usize(self.size_x)),
+                Some(CollisionTile::None | CollisionTile::Door)
+            )
+            || !self.properties_at(pos).walkable
+    }
+
+    //checks collision for an entity surfing on a water stray, which can move over water and back onto land
+    pub fn check_surf_collision(&self, pos: Vec2) -> bool {
+        let in_bounds = pos.0 >= 0.0
+            && pos.1 >= 0.0
+            && pos.0 < self.size_x as f32
+            && pos.1 < self.size_y as f32;
+        !(in_bounds && self.properties_at(pos).water) && self.check_collision(pos)
+    }
+
+    //returns the kind of encounters a tile causes, if any
+    pub fn encounter_kind(&self, pos: Vec2) -> Option<String> {
+        self.properties_at(pos).encounter
     }
 
     //checks encounter tiles on the map
     pub fn check_encounter(&self, pos: Vec2) -> bool {
-        //if check position is encounter tile
-        if let Some(tile) = self.walls.get(pos.to_usize(self.size_x)) {
-            matches!(
-                tile,
-                WallTile::TGRASS_1
-                    | WallTile::TGRASS_2
-                    | WallTile::TGRASS_3
-                    | WallTile::TGRASS_4
-            )
-        } else {
-            false
-        }
-        //true
+        self.encounter_kind(pos).is_some()
     }
 }
@@ -0,0 +1,183 @@
+/****************************************************/
+// Description: Autotiling of terrain layers, which store the kind of ground on each tile (grass, water, sand, path)
+// and are turned into floor tiles with the matching edges and corners when a map is loaded
+/****************************************************/
+//num_derive's FromPrimitive derive places its impl inside a const block, which rustc warns about
+#![allow(non_local_definitions)]
+
+use num_derive::FromPrimitive;
+use serde::Deserialize;
+
+use super::FloorTile;
+
+#[derive(FromPrimitive, Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Terrain {
+    Grass,
+    Water,
+    Sand,
+    Path,
+}
+
+//the floor tiles of a terrain drawn on grass, in the order
+//top left, top, top right, left, center, right, bottom left, bottom, bottom right
+//followed by the inner corners used when only a diagonal neighbour is grass (top left, top right, bottom left, bottom right)
+struct TileSet {
+    edges: [FloorTile; 9],
+    inner_corners: [FloorTile; 4],
+}
+
+const WATER: TileSet = TileSet {
+    edges: [
+        FloorTile::WGTL,
+        FloorTile::WGT,
+        FloorTile::WGTR,
+        FloorTile::WGL,
+        FloorTile::WATER1,
+        FloorTile::WGR,
+        FloorTile::WGBL,
+        FloorTile::WGB,
+        FloorTile::WGBR,
+    ],
+    //the corners of a grass island in water
+    inner_corners: [
+        FloorTile::GWBR,
+        FloorTile::GWBL,
+        FloorTile::GWTR,
+        FloorTile::GWTL,
+    ],
+};
+
+const SAND: TileSet = TileSet {
+    edges: [
+        FloorTile::SGTL,
+        FloorTile::SGTM,
+        FloorTile::SGTR,
+        FloorTile::SGML,
+        FloorTile::SGC,
+        FloorTile::SGMR,
+        FloorTile::SGBL,
+        FloorTile::SGBM,
+        FloorTile::SGBR,
+    ],
+    //there are no inner corner sprites for sand
+    inner_corners: [FloorTile::SGC; 4],
+};
+
+const PATH: TileSet = TileSet {
+    edges: [
+        FloorTile::PGTL,
+        FloorTile::PGT,
+        FloorTile::PGTR,
+        FloorTile::PGL,
+        FloorTile::PATH,
+        FloorTile::PGR,
+        FloorTile::PGBL,
+        FloorTile::PGB,
+        FloorTile::PGBR,
+    ],
+    //the corners of a grass island in a path
+    inner_corners: [
+        FloorTile::GPBR,
+        FloorTile::GPBL,
+        FloorTile::GPTR,
+        FloorTile::GPTL,
+    ],
+};
+
+//turns a terrain layer into floor tiles
+//takes in the terrain of every tile and the dimensions of the map
+//returns the floor tile for every tile of the map
+pub fn autotile(terrain: &[Terrain], size_x: usize, size_y: usize) -> Vec<FloorTile> {
+    (0..size_x * size_y)
+        .map(|i| {
+            let (x, y) = ((i % size_x) as i32, (i / size_x) as i32);
+            floor_tile(terrain, size_x, size_y, x, y)
+        })
+        .collect()
+}
+
+//picks the floor tile of a single tile from its terrain and the terrain around it
+fn floor_tile(terrain: &[Terrain], size_x: usize, size_y: usize, x: i32, y: i32) -> FloorTile {
+    let at = |x: i32, y: i32| -> Option<Terrain> {
+        //tiles outside of the map continue the terrain at its border, so terrain cut off by the edge has no border
+        let x = x.clamp(0, size_x as i32 - 1) as usize;
+        let y = y.clamp(0, size_y as i32 - 1) as usize;
+        terrain.get(y * size_x + x).copied()
+    };
+
+    let Some(kind) = at(x, y) else {
+        return FloorTile::GRASS1;
+    };
+    let tileset = match kind {
+        //mix in the second grass sprite so large fields don't look tiled
+        Terrain::Grass if (x * 7 + y * 3) % 5 == 0 => return FloorTile::GRASS2,
+        Terrain::Grass => return FloorTile::GRASS1,
+        Terrain::Water => &WATER,
+        Terrain::Sand => &SAND,
+        Terrain::Path => &PATH,
+    };
+    let same = |dx: i32, dy: i32| at(x + dx, y + dy).is_none_or(|t| t == kind);
+
+    //rows and columns of the 3x3 edge tiles: 0 if the terrain ends before this tile, 2 if it ends after it
+    let column = match (same(-1, 0), same(1, 0)) {
+        (false, _) => 0,
+        (true, false) => 2,
+        (true, true) => 1,
+    };
+    let row = match (same(0, -1), same(0, 1)) {
+        (false, _) => 0,
+        (true, false) => 2,
+        (true, true) => 1,
+    };
+    if (row, column) != (1, 1) {
+        return tileset.edges[row * 3 + column];
+    }
+
+    //surrounded on all four sides, but a diagonal neighbour can still be a different terrain
+    let diagonals = [same(-1, -1), same(1, -1), same(-1, 1), same(1, 1)];
+    match diagonals.iter().position(|same| !same) {
+        Some(corner) => tileset.inner_corners[corner],
+        None => tileset.edges[4],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //reads a terrain layer from rows of characters, g for grass and w for water
+    fn terrain(rows: &[&str]) -> Vec<Terrain> {
+        rows.iter()
+            .flat_map(|row| row.chars())
+            .map(|c| match c {
+                'w' => Terrain::Water,
+                _ => Terrain::Grass,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn single_water_tile() {
+        let tiles = autotile(&terrain(&["ggg", "gwg", "ggg"]), 3, 3);
+        //a lone tile ends on every side, which picks the top left edge
+        assert!(matches!(tiles[4], FloorTile::WGTL));
+        assert!(matches!(tiles[0], FloorTile::GRASS1 | FloorTile::GRASS2));
+    }
+
+    #[test]
+    fn straight_shore() {
+        let tiles = autotile(&terrain(&["ggg", "ggg", "www", "www"]), 3, 4);
+        //the map's edges continue the water, so the whole shore is a straight top edge
+        assert!(tiles[6..9].iter().all(|tile| matches!(tile, FloorTile::WGT)));
+        assert!(tiles[9..12].iter().all(|tile| matches!(tile, FloorTile::WATER1)));
+    }
+
+    #[test]
+    fn inner_corner() {
+        let tiles = autotile(&terrain(&["gww", "www", "www"]), 3, 3);
+        //only the top left diagonal is grass, so the center is the bottom right corner of that grass
+        assert!(matches!(tiles[4], FloorTile::GWBR));
+        assert!(matches!(tiles[8], FloorTile::WATER1));
+    }
+}
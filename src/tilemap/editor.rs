@@ -0,0 +1,148 @@
+/****************************************************/
+// Description: Reader for maps exported by the web map editor in mapeditor/, a zip holding a "map" folder
+// with floor.txt, walls.txt, collision.txt and objects.txt, where objects are turned into entity spawns
+/****************************************************/
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::Read,
+    path::Path,
+};
+
+use zip::ZipArchive;
+
+use crate::components::bag::Item;
+
+use super::{
+    error::{tokens, MapError, MapLoadError},
+    format::{EntitySpawn, LayerData, LayerKind, MapFile},
+};
+
+//the files the editor exports, objects.txt is missing from exports made before objects were saved
+const FILES: [&str; 4] = ["floor.txt", "walls.txt", "collision.txt", "objects.txt"];
+
+//ids of the objects layer, matching the objects list in mapeditor/tiles.js
+const BERRY1: u32 = 1;
+const BERRY2: u32 = 2;
+const DOOR: u32 = 3;
+
+//reads a zip exported by the map editor into a map file
+//the map is named after the zip and has no metadata, which can be added by converting it to json
+pub fn read_zip(path: &Path) -> Result<MapFile, MapLoadError> {
+    let io_error = |message: String| MapLoadError::single(path.to_path_buf(), MapError::Io { message });
+
+    let file = File::open(path).map_err(|e| io_error(e.to_string()))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| io_error(e.to_string()))?;
+
+    //the files are found by name, whatever folder the editor put them in
+    let mut files = HashMap::new();
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| io_error(e.to_string()))?;
+        let Some(name) = FILES.iter().find(|name| entry.name().rsplit('/').next() == Some(name)) else {
+            continue;
+        };
+        let mut text = String::new();
+        entry
+            .read_to_string(&mut text)
+            .map_err(|e| io_error(format!("{}: {}", name, e)))?;
+        files.insert(*name, text);
+    }
+
+    let name = path
+        .file_stem()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    build(name, &files).map_err(|errors| MapLoadError {
+        file: path.to_path_buf(),
+        errors,
+    })
+}
+
+//splits one of the exported text files into the rows of the map
+fn rows(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+//turns the objects layer into the entities it spawns
+//takes in the text of objects.txt, so invalid ids can be reported with their line and column in it
+fn objects(text: &str, errors: &mut Vec<MapError>) -> Vec<EntitySpawn> {
+    let mut entities = vec![];
+    let lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    for (y, (line, row)) in lines.enumerate() {
+        for (x, (offset, id)) in tokens(row).enumerate() {
+            let (x, y) = (x as i32, y as i32);
+            match id.parse::<u32>() {
+                Ok(0) => {}
+                Ok(BERRY1 | BERRY2) => entities.push(EntitySpawn::Item {
+                    x,
+                    y,
+                    item: Item::Berry,
+                    amount: 1,
+                }),
+                Ok(DOOR) => entities.push(EntitySpawn::Door { x, y }),
+                _ => errors.push(MapError::InvalidTile {
+                    layer: String::from("objects"),
+                    row: y as usize,
+                    column: x as usize,
+                    value: id.to_string(),
+                    position: Some((line + 1, row[..offset].chars().count() + 1)),
+                }),
+            }
+        }
+    }
+    entities
+}
+
+//builds a map file from the contents of the exported files
+//the editor does not export its dimensions, so they are taken from the floor layer
+fn build(name: String, files: &HashMap<&str, String>) -> Result<MapFile, Vec<MapError>> {
+    let mut errors = vec![];
+    let mut read = |file: &str| match files.get(file) {
+        Some(text) => rows(text),
+        None => {
+            errors.push(MapError::Io {
+                message: format!("{} is missing from the export", file),
+            });
+            vec![]
+        }
+    };
+
+    let floor = read("floor.txt");
+    let walls = read("walls.txt");
+    let collision = read("collision.txt");
+    let objects = objects(files.get("objects.txt").map(String::as_str).unwrap_or_default(), &mut errors);
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(MapFile {
+        name,
+        music: None,
+        indoors: false,
+        width: floor.first().map_or(0, |row| row.split_whitespace().count()),
+        height: floor.len(),
+        spawn: None,
+        layers: vec![
+            LayerData {
+                name: String::from("floor"),
+                kind: LayerKind::Floor,
+                tiles: floor,
+            },
+            LayerData {
+                name: String::from("walls"),
+                kind: LayerKind::Wall,
+                tiles: walls,
+            },
+        ],
+        collision,
+        encounter_zones: vec![],
+        warps: vec![],
+        entities: objects,
+        triggers: vec![],
+    })
+}
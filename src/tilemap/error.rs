@@ -0,0 +1,262 @@
+/****************************************************/
+// Description: Errors found while loading a map, collected so that every problem in a map is reported at once
+/****************************************************/
+use std::{collections::HashMap, fmt, ops::Range, path::PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapError {
+    //the map file could not be read
+    Io { message: String },
+    //the file is not valid json, or is missing or has wrong fields for the map format
+    Syntax { line: usize, column: usize, message: String },
+    //a tile id that is not a number or not a tile of the layer's kind
+    //row and column are the tile coordinates of the id within the layer,
+    //and position is the line and column of the id in the file, when it could be found
+    InvalidTile {
+        layer: String,
+        row: usize,
+        column: usize,
+        value: String,
+        position: Option<(usize, usize)>,
+    },
+    //a layer with a different number of rows than the height of the map
+    RowCount { layer: String, expected: usize, found: usize },
+    //a row with a different number of tiles than the width of the map
+    RowLength { layer: String, row: usize, expected: usize, found: usize },
+    //an encounter zone listing a species that does not exist
+    UnknownSpecies { zone: usize, species: String },
+    //any other problem with a part of the map, such as an object missing a property
+    Invalid { location: String, message: String },
+}
+
+//every problem found in one map file
+#[derive(Debug, Clone)]
+pub struct MapLoadError {
+    pub file: PathBuf,
+    pub errors: Vec<MapError>,
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::Io { message } => write!(f, "{}", message),
+            MapError::Syntax { line, column, message } => {
+                write!(f, "line {} column {}: {}", line, column, message)
+            }
+            MapError::InvalidTile { layer, row, column, value, position } => {
+                if let Some((line, column)) = position {
+                    write!(f, "line {} column {}: ", line, column)?;
+                }
+                write!(
+                    f,
+                    "layer \"{}\" row {} column {}: \"{}\" is not a valid tile id",
+                    layer, row, column, value
+                )
+            }
+            MapError::RowCount { layer, expected, found } => write!(
+                f,
+                "layer \"{}\" has {} rows but the map is {} tiles tall",
+                layer, found, expected
+            ),
+            MapError::RowLength { layer, row, expected, found } => write!(
+                f,
+                "layer \"{}\" row {} has {} tiles but the map is {} tiles wide",
+                layer, row, found, expected
+            ),
+            MapError::UnknownSpecies { zone, species } => {
+                write!(f, "encounter zone {}: unknown species \"{}\"", zone, species)
+            }
+            MapError::Invalid { location, message } => write!(f, "{}: {}", location, message),
+        }
+    }
+}
+
+impl fmt::Display for MapLoadError {
+    //one line per problem, each starting with the file it was found in
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self
+            .errors
+            .iter()
+            .map(|error| format!("{}: {}", self.file.display(), error))
+            .collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl std::error::Error for MapError {}
+impl std::error::Error for MapLoadError {}
+
+impl MapLoadError {
+    //creates an error for a file with a single problem
+    pub fn single(file: PathBuf, error: MapError) -> Self {
+        Self {
+            file,
+            errors: vec![error],
+        }
+    }
+}
+
+impl From<&serde_json::Error> for MapError {
+    fn from(e: &serde_json::Error) -> Self {
+        //serde_json appends the position to its messages, which is already kept separately
+        let position = format!(" at line {} column {}", e.line(), e.column());
+        let message = e.to_string();
+        MapError::Syntax {
+            line: e.line(),
+            column: e.column(),
+            message: message.trim_end_matches(&position).to_string(),
+        }
+    }
+}
+
+//splits a row of tile ids into the ids and the byte offset each one starts at
+pub fn tokens(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut end = 0;
+    text.split_whitespace().map(move |token| {
+        let start = end + text[end..].find(token).unwrap_or(0);
+        end = start + token.len();
+        (start, token)
+    })
+}
+
+//returns the line and column of a byte offset in a text, both starting at 1
+pub fn text_position(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+//a step into a json value, either a key of an object or an index of an array
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+//finds where the rows of tiles are in the text of a json map, which is already known to be valid json
+//returns the byte range inside the quotes of every row, keyed by the index of its layer
+//(or None for the collision layer) and the row
+pub fn json_rows(text: &str) -> HashMap<(Option<usize>, usize), Range<usize>> {
+    let mut rows = HashMap::new();
+    let mut path: Vec<Step> = vec![];
+    let mut expect_key = false; //whether the next string is the key of an object
+    let mut chars = text.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '{' => {
+                path.push(Step::Key(String::new()));
+                expect_key = true;
+            }
+            '[' => path.push(Step::Index(0)),
+            '}' | ']' => {
+                path.pop();
+            }
+            ',' => match path.last_mut() {
+                Some(Step::Index(index)) => *index += 1,
+                Some(Step::Key(_)) => expect_key = true,
+                None => {}
+            },
+            '"' => {
+                let mut value = String::new();
+                let mut escaped = false;
+                let mut end = text.len();
+                for (j, c) in chars.by_ref() {
+                    match (escaped, c) {
+                        (false, '\\') => escaped = true,
+                        (false, '"') => {
+                            end = j;
+                            break;
+                        }
+                        _ => {
+                            escaped = false;
+                            value.push(c);
+                        }
+                    }
+                }
+
+                if expect_key {
+                    if let Some(Step::Key(key)) = path.last_mut() {
+                        *key = value;
+                    }
+                    expect_key = false;
+                    continue;
+                }
+                let row = match path.as_slice() {
+                    [Step::Key(layers), Step::Index(layer), Step::Key(tiles), Step::Index(row)]
+                        if layers == "layers" && tiles == "tiles" =>
+                    {
+                        (Some(*layer), *row)
+                    }
+                    [Step::Key(collision), Step::Index(row)] if collision == "collision" => (None, *row),
+                    _ => continue,
+                };
+                rows.insert(row, i + 1..end);
+            }
+            _ => {}
+        }
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //finds the line and column of the id at a row and column of a layer (None for the collision layer)
+    fn locate(text: &str, layer: Option<usize>, row: usize, column: usize) -> Option<(usize, usize)> {
+        let range = json_rows(text).get(&(layer, row))?.clone();
+        let (offset, _) = tokens(&text[range.clone()]).nth(column)?;
+        Some(text_position(text, range.start + offset))
+    }
+
+    const MAP: &str = r#"{
+  "name": "test",
+  "layers": [
+    {
+      "name": "floor",
+      "kind": "floor",
+      "tiles": [
+        "1 2 1",
+        "1 oops 1"
+      ]
+    }
+  ],
+  "collision": [
+    "0 0 0",
+    "0 0  9"
+  ]
+}"#;
+
+    #[test]
+    fn invalid_floor_tile() {
+        assert_eq!(locate(MAP, Some(0), 1, 1), Some((9, 12)));
+    }
+
+    #[test]
+    fn collision_rows() {
+        assert_eq!(locate(MAP, None, 0, 0), Some((14, 6)));
+        assert_eq!(locate(MAP, None, 1, 2), Some((15, 11)));
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        let text = MAP.replace('\n', "\r\n");
+        assert_eq!(locate(&text, Some(0), 1, 1), Some((9, 12)));
+        assert_eq!(locate(&text, None, 1, 2), Some((15, 11)));
+    }
+
+    #[test]
+    fn escaped_quotes() {
+        let text = r#"{"name": "the \"old\" \\ town", "layers": [{"name": "a \"b\"", "tiles": ["1 x"]}], "collision": ["0 y"]}"#;
+        assert_eq!(locate(text, Some(0), 0, 1), Some((1, 77)));
+        assert_eq!(locate(text, None, 0, 1), Some((1, 101)));
+    }
+
+    #[test]
+    fn multibyte_characters() {
+        let text = "{\n  \"name\": \"café\", \"collision\": [\"0 é 0 x\"]\n}";
+        assert_eq!(locate(text, None, 0, 1), Some((2, 36)));
+        assert_eq!(locate(text, None, 0, 3), Some((2, 40)));
+    }
+}
@@ -0,0 +1,251 @@
+/****************************************************/
+// Description: Data structures for the map file format, a single json file per map
+// holding the dimensions, tile layers, collision and all map metadata
+/****************************************************/
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{bag::Item, stray::Stray, PathMode},
+    script::Condition,
+    vec2::{Direction, Vec2},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MapFile {
+    pub name: String,
+    #[serde(default)]
+    pub music: Option<String>, //path of the music to play while on the map
+    #[serde(default)]
+    pub indoors: bool, //inside a building (or cave), where the lead stray doesn't follow the player
+    pub width: usize,
+    pub height: usize,
+    #[serde(default)]
+    pub spawn: Option<(i32, i32)>, //tile the player starts on when the game begins on this map
+    pub layers: Vec<LayerData>, //tile layers, drawn in order within their kind
+    pub collision: Vec<String>, //one row of the map per string, each tile id separated by whitespace
+    #[serde(default)]
+    pub encounter_zones: Vec<EncounterZone>,
+    #[serde(default)]
+    pub warps: Vec<Warp>,
+    #[serde(default)]
+    pub entities: Vec<EntitySpawn>,
+    #[serde(default)]
+    pub triggers: Vec<Trigger>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LayerKind {
+    Floor, //FloorTile ids drawn below everything
+    Wall,  //WallTile ids drawn below entities, except for tiles in the front filter
+    Front, //WallTile ids always drawn above entities
+    Terrain, //Terrain ids (0 grass, 1 water, 2 sand, 3 path), autotiled into a floor layer when the map is loaded
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LayerData {
+    pub name: String,
+    pub kind: LayerKind,
+    pub tiles: Vec<String>, //one row of the map per string, each tile id separated by whitespace
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EncounterSlot {
+    pub species: String,
+    #[serde(default = "default_weight")]
+    pub weight: u32, //relative chance of this species being picked
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EncounterZone {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    #[serde(default = "default_encounter_kind")]
+    pub kind: String, //kind of encounter tiles the zone is for, such as "grass" or "water"
+    pub strays: Vec<EncounterSlot>,
+    #[serde(default = "default_max_opponents")]
+    pub max_opponents: usize, //an encounter has between 1 and this many wild strays
+    #[serde(default)]
+    pub roaming: usize, //wild strays roaming the zone in plain sight, which replace its random encounters when above 0
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WarpTrigger {
+    #[default]
+    Step,     //warps as soon as the player finishes walking onto the tile
+    Interact, //warps when the player faces the tile and presses the interact button
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Warp {
+    pub x: i32,
+    pub y: i32,
+    pub target_map: i32,
+    pub target_x: i32,
+    pub target_y: i32,
+    #[serde(default)]
+    pub facing: Option<Direction>, //direction the player faces after warping, unchanged if not given
+    #[serde(default)]
+    pub trigger: WarpTrigger,
+    #[serde(default)]
+    pub condition: Option<Condition>, //the warp only works while this holds, such as a story flag being set
+    #[serde(default)]
+    pub locked_says: Option<String>, //shown when the warp is taken while its condition doesn't hold
+}
+
+//an area of the map that runs an event script when the player steps into it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Trigger {
+    pub x: i32,
+    pub y: i32,
+    #[serde(default = "default_size")]
+    pub width: i32,
+    #[serde(default = "default_size")]
+    pub height: i32,
+    pub script: String, //name of the script in scripts/
+    #[serde(default)]
+    pub once: bool, //only runs the first time, remembered with the story flag "script:<name>"
+}
+
+//an item sold in a shopkeeper npc's shop
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ShopItem {
+    pub item: Item,
+    #[serde(default)]
+    pub price: Option<u32>, //the item's usual price if not given
+}
+
+//an npc placed on a map, boxed in EntitySpawn as it is much larger than the other kinds of entities
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NpcSpawn {
+    pub x: i32,
+    pub y: i32,
+    pub sprite: String, //path of the character sprite sheet
+    #[serde(default)]
+    pub name: Option<String>, //shown above the npc's dialogue
+    #[serde(default)]
+    pub says: String,
+    #[serde(default)]
+    pub id: Option<String>, //name event scripts use to refer to the npc
+    #[serde(default)]
+    pub script: Option<String>, //event script run when talked to, instead of saying something
+    #[serde(default)]
+    pub condition: Option<Condition>, //the npc is only spawned if this holds when the player enters the map
+    #[serde(default)]
+    pub path: Vec<Direction>, //directions walked one tile at a time, the npc stands still if empty
+    #[serde(default)]
+    pub path_mode: PathMode, //whether the path loops, is walked back and forth, or is walked once
+    #[serde(default)]
+    pub facing: Option<Direction>, //direction a standing npc faces, down if not given
+    #[serde(default)]
+    pub wander: Option<i32>, //radius of tiles a npc without a path wanders around in
+    #[serde(default)]
+    pub look_around: bool, //a npc without a path or wander radius turns to look around
+    #[serde(default)]
+    pub step_delay: Option<f32>, //ms waited after each step, no wait on paths and 2 seconds otherwise if not given
+    #[serde(default)]
+    pub shop: Vec<ShopItem>, //items sold by the npc, who opens their shop after saying something
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EntitySpawn {
+    Npc(Box<NpcSpawn>),
+    //items stay gone once picked up, remembered with the story flag "item:<map id>:<x>:<y>"
+    Item {
+        x: i32,
+        y: i32,
+        item: Item,
+        #[serde(default = "default_amount")]
+        amount: u32,
+    },
+    Sign {
+        x: i32,
+        y: i32,
+        says: String,
+    },
+    Door {
+        x: i32,
+        y: i32,
+    },
+    //spirit shrines heal the party and are where the player returns to after losing a battle
+    Shrine {
+        x: i32,
+        y: i32,
+    },
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+fn default_encounter_kind() -> String {
+    String::from("grass")
+}
+
+fn default_max_opponents() -> usize {
+    1
+}
+
+fn default_amount() -> u32 {
+    1
+}
+
+fn default_size() -> i32 {
+    1
+}
+
+impl Trigger {
+    //checks if a tile position is inside the trigger's area
+    pub fn contains(&self, pos: Vec2) -> bool {
+        let Vec2(x, y) = pos.round_to_tile();
+        x >= self.x as f32
+            && y >= self.y as f32
+            && x < (self.x + self.width) as f32
+            && y < (self.y + self.height) as f32
+    }
+
+    //the story flag remembering that a trigger that only runs once has been run
+    pub fn flag(&self) -> String {
+        format!("script:{}", self.script)
+    }
+}
+
+impl EncounterZone {
+    //checks if a tile position is inside the zone
+    pub fn contains(&self, pos: Vec2) -> bool {
+        let Vec2(x, y) = pos.round_to_tile();
+        x >= self.x as f32
+            && y >= self.y as f32
+            && x < (self.x + self.width) as f32
+            && y < (self.y + self.height) as f32
+    }
+
+    //picks the wild strays for an encounter in this zone
+    //with rare set, the weights are flipped around so the rarest strays are the most common
+    //returns the opponent team, which is empty if the zone has no valid strays
+    pub fn roll_opponents(&self, rng: &mut impl Rng, rare: bool) -> [Option<Stray>; 4] {
+        let mut opponents = [None, None, None, None];
+        let count = rng.gen_range(1..=self.max_opponents.clamp(1, 4));
+        for opponent in opponents.iter_mut().take(count) {
+            *opponent = self.roll_stray(rng, rare);
+        }
+        opponents
+    }
+
+    //picks one wild stray living in this zone, such as one roaming it in plain sight
+    //returns None if the zone has no valid strays
+    pub fn roll_stray(&self, rng: &mut impl Rng, rare: bool) -> Option<Stray> {
+        let max_weight = self.strays.iter().map(|slot| slot.weight).max().unwrap_or(0);
+        let weights = WeightedIndex::new(self.strays.iter().map(|slot| match rare {
+            true => max_weight + 1 - slot.weight,
+            false => slot.weight,
+        }))
+        .ok()?;
+        Stray::from_species(&self.strays[weights.sample(rng)].species, false)
+    }
+}
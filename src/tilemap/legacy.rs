@@ -0,0 +1,74 @@
+/****************************************************/
+// Description: Reader for the old map folders made of dim.txt, floor.txt, walls.txt and collision.txt,
+// used to convert them into the single file map format
+/****************************************************/
+use std::{fs, path::Path};
+
+use super::format::{LayerData, LayerKind, MapFile};
+
+//reads one of the whitespace separated text files and splits its ids back into rows of the map
+//takes in the folder, the file name, and the dimensions of the map
+//returns the rows, or a message describing what is wrong with the file
+fn read_rows(folder: &Path, file: &str, size_x: usize, size_y: usize) -> Result<Vec<String>, String> {
+    let path = folder.join(file);
+    let text = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let ids: Vec<&str> = text.split_whitespace().collect();
+
+    if ids.len() != size_x * size_y {
+        return Err(format!(
+            "{}: expected {} values but found {}",
+            path.display(),
+            size_x * size_y,
+            ids.len()
+        ));
+    }
+
+    Ok(ids.chunks(size_x).map(|row| row.join(" ")).collect())
+}
+
+//converts an old map folder into a map file
+//the map is named after its folder and has no metadata, which can be added to the new file afterwards
+pub fn read_folder(folder: &Path) -> Result<MapFile, String> {
+    let dim_path = folder.join("dim.txt");
+    let dim: Vec<usize> = fs::read_to_string(&dim_path)
+        .map_err(|e| format!("{}: {}", dim_path.display(), e))?
+        .split_whitespace()
+        .map(|x| x.parse::<usize>())
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("{}: {}", dim_path.display(), e))?;
+
+    let [size_x, size_y] = dim[..] else {
+        return Err(format!("{}: does not contain exactly 2 values", dim_path.display()));
+    };
+
+    let name = folder
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Ok(MapFile {
+        name,
+        music: None,
+        indoors: false,
+        width: size_x,
+        height: size_y,
+        spawn: None,
+        layers: vec![
+            LayerData {
+                name: String::from("floor"),
+                kind: LayerKind::Floor,
+                tiles: read_rows(folder, "floor.txt", size_x, size_y)?,
+            },
+            LayerData {
+                name: String::from("walls"),
+                kind: LayerKind::Wall,
+                tiles: read_rows(folder, "walls.txt", size_x, size_y)?,
+            },
+        ],
+        collision: read_rows(folder, "collision.txt", size_x, size_y)?,
+        encounter_zones: vec![],
+        warps: vec![],
+        entities: vec![],
+        triggers: vec![],
+    })
+}
@@ -0,0 +1,113 @@
+/****************************************************/
+// Description: Properties of each kind of tile, loaded from assets/tiles/properties.json
+// such as whether it can be walked on, the encounters it causes and whether it is drawn above the player
+/****************************************************/
+use std::{fs, sync::OnceLock};
+
+use enum_map::EnumMap;
+use serde::Deserialize;
+
+use crate::vec2::Direction;
+
+use super::{FloorTile, WallTile};
+
+pub const PROPERTIES_PATH: &str = "assets/tiles/properties.json";
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct TileProperties {
+    pub walkable: bool,            //false if the tile blocks movement, even when the collision layer does not
+    pub encounter: Option<String>, //kind of wild encounters the tile causes, such as "grass" or "water"
+    pub above_player: bool,        //wall tiles drawn above entities, so the player can walk behind them
+    pub water: bool,
+    pub ledge: Option<Direction>, //direction the tile can be hopped down in
+    pub slippery: bool,           //entities keep sliding over the tile until they are stopped
+    pub spin: Option<Direction>,  //direction entities stepping on the tile are pushed in
+    pub speed: f32,               //multiplier of the speed of entities walking onto the tile, such as 0.5 in deep snow
+    pub damaging: bool,           //hurts the player's strays when stepped on
+}
+
+impl Default for TileProperties {
+    fn default() -> Self {
+        Self {
+            walkable: true,
+            encounter: None,
+            above_player: false,
+            water: false,
+            ledge: None,
+            slippery: false,
+            spin: None,
+            speed: 1.0,
+            damaging: false,
+        }
+    }
+}
+
+//a group of tiles sharing the same properties
+#[derive(Deserialize, Debug)]
+struct PropertyGroup<T> {
+    tiles: Vec<T>,
+    #[serde(flatten)]
+    properties: TileProperties,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct PropertiesFile {
+    #[serde(default)]
+    floor: Vec<PropertyGroup<FloorTile>>,
+    #[serde(default)]
+    wall: Vec<PropertyGroup<WallTile>>,
+}
+
+//the properties of every floor and wall tile, tiles missing from the file have the default properties
+#[derive(Default, Debug, Clone)]
+pub struct TilePropertyTable {
+    pub floor: EnumMap<FloorTile, TileProperties>,
+    pub wall: EnumMap<WallTile, TileProperties>,
+}
+
+impl TilePropertyTable {
+    //returns the tile properties shared by every map, which are loaded from their data file the first time
+    pub fn shared() -> Result<&'static Self, String> {
+        static TABLE: OnceLock<Result<TilePropertyTable, String>> = OnceLock::new();
+        TABLE.get_or_init(Self::load).as_ref().map_err(Clone::clone)
+    }
+
+    //loads the tile properties from their data file
+    fn load() -> Result<Self, String> {
+        let text = fs::read_to_string(PROPERTIES_PATH).map_err(|e| e.to_string())?;
+        let file: PropertiesFile = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+
+        let mut table = Self::default();
+        for group in file.floor {
+            for tile in group.tiles {
+                table.floor[tile] = group.properties.clone();
+            }
+        }
+        for group in file.wall {
+            for tile in group.tiles {
+                table.wall[tile] = group.properties.clone();
+            }
+        }
+        Ok(table)
+    }
+}
+
+impl TileProperties {
+    //combines the properties of the tiles stacked on one spot of the map, given from the bottom layer up
+    //the spot blocks movement if any tile does, upper tiles decide the encounter kind, ledge and spin direction
+    //and the slowest tile decides the speed
+    pub fn combine<'a>(tiles: impl Iterator<Item = &'a TileProperties>) -> TileProperties {
+        tiles.fold(TileProperties::default(), |combined, tile| TileProperties {
+            walkable: combined.walkable && tile.walkable,
+            encounter: tile.encounter.clone().or(combined.encounter),
+            above_player: combined.above_player || tile.above_player,
+            water: combined.water || tile.water,
+            ledge: tile.ledge.or(combined.ledge),
+            slippery: combined.slippery || tile.slippery,
+            spin: tile.spin.or(combined.spin),
+            speed: combined.speed.min(tile.speed),
+            damaging: combined.damaging || tile.damaging,
+        })
+    }
+}
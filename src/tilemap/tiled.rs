@@ -0,0 +1,631 @@
+/****************************************************/
+// Description: Reader for maps made in the Tiled editor (.tmx maps with inline or .tsx tilesets)
+// tilesets have to use tilesprites.png, so that each tile can be matched to its FloorTile or WallTile
+// - tile layers become floor, wall or front layers, from their "kind" property or their name
+// - tile properties "collision" (wall or door) and "encounter" (a kind such as grass) set collision and encounter zones
+// - the strays of an encounter kind are listed by the map properties "encounters.<kind>" (e.g. "Carerus:2, Omikae")
+//   and "encounters.<kind>.max_opponents"
+// - objects of type spawn, npc, item, sign, door and warp become the player spawn, entities and warps
+/****************************************************/
+use std::{collections::HashMap, fs, path::Path};
+
+use enum_map::Enum;
+use roxmltree::{Document, Node};
+use serde::de::DeserializeOwned;
+
+use crate::script::Condition;
+
+use super::{
+    error::{MapError, MapLoadError},
+    format::{
+        EncounterSlot, EncounterZone, EntitySpawn, LayerData, LayerKind, MapFile, NpcSpawn, ShopItem,
+        Trigger, Warp,
+    },
+    autotile::Terrain,
+    FloorTile, WallTile,
+};
+
+//the only image tilesets can use
+const TILESHEET: &str = "tilesprites.png";
+//tiled stores flipping and rotation in the highest bits of a tile id
+const FLIP_FLAGS: u32 = 0xF000_0000;
+
+type Properties = HashMap<String, String>;
+
+struct Tileset {
+    first_gid: u32,
+    columns: u32,
+    tile_w: u32,
+    tile_h: u32,
+    margin: u32,
+    spacing: u32,
+    tiles: HashMap<u32, Properties>, //properties of each tile, by id within the tileset
+}
+
+//what the tile properties of every layer add up to on each tile of the map
+struct TileData {
+    collision: Vec<u8>,
+    encounter: Vec<Option<String>>,
+}
+
+//reads the custom properties of a map, layer, tile or object
+fn properties(node: Node) -> Properties {
+    node.children()
+        .filter(|child| child.has_tag_name("properties"))
+        .flat_map(|props| props.children().filter(|prop| prop.has_tag_name("property")))
+        .filter_map(|prop| {
+            let value = prop.attribute("value").or_else(|| prop.text()).unwrap_or("");
+            Some((prop.attribute("name")?.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+//reads the condition of an npc or warp from its "condition.<field>" properties, such as "condition.flag"
+//returns None if there are none
+fn condition(props: &Properties) -> Option<Condition> {
+    let get = |field: &str| props.get(&format!("condition.{}", field));
+    let condition = Condition {
+        flag: get("flag").cloned(),
+        not_flag: get("not_flag").cloned(),
+        item: get("item").and_then(|item| parse_enum(item)),
+        amount: get("amount").and_then(|amount| amount.parse().ok()),
+        counter: get("counter").cloned(),
+        at_least: get("at_least").and_then(|value| value.parse().ok()),
+        below: get("below").and_then(|value| value.parse().ok()),
+    };
+    props
+        .keys()
+        .any(|key| key.starts_with("condition."))
+        .then_some(condition)
+}
+
+//reads a numeric attribute, which defaults to 0 when missing
+fn number(node: Node, attribute: &str) -> u32 {
+    node.attribute(attribute)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0)
+}
+
+//parses a value into one of the enums used by the map format, such as a Direction or an Item
+fn parse_enum<T: DeserializeOwned>(value: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(value.trim().to_string())).ok()
+}
+
+//returns the line and column of a byte offset in the file a node was read from
+fn text_pos(node: Node, offset: usize) -> (usize, usize) {
+    let pos = node.document().text_pos_at(offset);
+    (pos.row as usize, pos.col as usize)
+}
+
+fn syntax_error(e: &roxmltree::Error) -> MapError {
+    let pos = e.pos();
+    MapError::Syntax {
+        line: pos.row as usize,
+        column: pos.col as usize,
+        message: e.to_string(),
+    }
+}
+
+//reads a tileset, loading it from its .tsx file if it is not inside the map
+fn read_tileset(node: Node, folder: &Path) -> Result<Tileset, MapError> {
+    let first_gid = number(node, "firstgid");
+
+    let Some(source) = node.attribute("source") else {
+        return parse_tileset(node, first_gid, "tileset");
+    };
+
+    let path = folder.join(source);
+    let text = fs::read_to_string(&path).map_err(|e| MapError::Io {
+        message: format!("{}: {}", path.display(), e),
+    })?;
+    let doc = Document::parse(&text).map_err(|e| MapError::Invalid {
+        location: path.display().to_string(),
+        message: syntax_error(&e).to_string(),
+    })?;
+    parse_tileset(doc.root_element(), first_gid, source)
+}
+
+fn parse_tileset(node: Node, first_gid: u32, name: &str) -> Result<Tileset, MapError> {
+    let image = node
+        .children()
+        .find(|child| child.has_tag_name("image"))
+        .and_then(|image| image.attribute("source"))
+        .unwrap_or("");
+    if !image.ends_with(TILESHEET) {
+        return Err(MapError::Invalid {
+            location: format!("tileset \"{}\"", name),
+            message: format!("uses \"{}\" but only {} is supported", image, TILESHEET),
+        });
+    }
+
+    let tiles = node
+        .children()
+        .filter(|child| child.has_tag_name("tile"))
+        .map(|tile| (number(tile, "id"), properties(tile)))
+        .collect();
+
+    Ok(Tileset {
+        first_gid,
+        columns: number(node, "columns").max(1),
+        tile_w: number(node, "tilewidth"),
+        tile_h: number(node, "tileheight"),
+        margin: number(node, "margin"),
+        spacing: number(node, "spacing"),
+        tiles,
+    })
+}
+
+//finds the tileset a tile belongs to
+//returns the position of the tile in tilesprites.png and its properties
+fn lookup(tilesets: &[Tileset], gid: u32) -> Option<((i32, i32), Option<&Properties>)> {
+    let gid = gid & !FLIP_FLAGS;
+    let tileset = tilesets
+        .iter()
+        .filter(|tileset| tileset.first_gid <= gid)
+        .max_by_key(|tileset| tileset.first_gid)?;
+
+    let id = gid - tileset.first_gid;
+    let x = tileset.margin + (id % tileset.columns) * (tileset.tile_w + tileset.spacing);
+    let y = tileset.margin + (id / tileset.columns) * (tileset.tile_h + tileset.spacing);
+    Some(((x as i32, y as i32), tileset.tiles.get(&id)))
+}
+
+//reads the tile ids of a layer, in csv or as a list of tile elements
+//each id comes with the byte offset it is written at in the file, for reporting invalid ids
+fn layer_gids(layer: Node, name: &str) -> Result<Vec<(u32, usize)>, MapError> {
+    let invalid = |message: &str| MapError::Invalid {
+        location: format!("layer \"{}\"", name),
+        message: message.to_string(),
+    };
+
+    let data = layer
+        .children()
+        .find(|child| child.has_tag_name("data"))
+        .ok_or_else(|| invalid("has no tile data"))?;
+    if data.children().any(|child| child.has_tag_name("chunk")) {
+        return Err(invalid("infinite maps are not supported"));
+    }
+
+    match data.attribute("encoding") {
+        Some("csv") => {
+            let Some(text) = data.first_child().filter(|child| child.is_text()) else {
+                return Ok(vec![]);
+            };
+            let mut offset = text.range().start;
+            text.text()
+                .unwrap_or("")
+                .split(',')
+                .map(|id| {
+                    let start = offset + id.len() - id.trim_start().len();
+                    offset += id.len() + 1;
+                    id.trim().parse::<u32>().map(|gid| (gid, start))
+                })
+                .collect::<Result<_, _>>()
+                .map_err(|e| invalid(&e.to_string()))
+        }
+        None => Ok(data
+            .children()
+            .filter(|child| child.has_tag_name("tile"))
+            .map(|tile| (number(tile, "gid"), tile.range().start))
+            .collect()),
+        Some(encoding) => Err(invalid(&format!(
+            "\"{}\" encoding is not supported, save the map with csv tile layer format",
+            encoding
+        ))),
+    }
+}
+
+//which layer of the game a tile layer becomes, from its "kind" property or otherwise its name
+fn layer_kind(name: &str, props: &Properties) -> LayerKind {
+    let kind = props
+        .get("kind")
+        .map(|kind| kind.to_lowercase())
+        .unwrap_or_else(|| name.to_lowercase());
+    if kind.contains("terrain") {
+        LayerKind::Terrain
+    } else if kind.contains("floor") {
+        LayerKind::Floor
+    } else if kind.contains("front") {
+        LayerKind::Front
+    } else {
+        LayerKind::Wall
+    }
+}
+
+//turns a tile layer into rows of tile ids, adding the properties of its tiles to the map's tile data
+fn read_layer(
+    layer: Node,
+    tilesets: &[Tileset],
+    width: usize,
+    data: &mut TileData,
+    errors: &mut Vec<MapError>,
+) -> Option<LayerData> {
+    let name = layer.attribute("name").unwrap_or("layer").to_string();
+    let kind = layer_kind(&name, &properties(layer));
+
+    let gids = match layer_gids(layer, &name) {
+        Ok(gids) => gids,
+        Err(e) => {
+            errors.push(e);
+            return None;
+        }
+    };
+
+    //tiles are matched to the game's tiles by where their sprite is in tilesprites.png
+    //terrain layers use the "terrain" property of their tiles instead
+    let ids: HashMap<(i32, i32), usize> = match kind {
+        LayerKind::Floor => (0..FloorTile::LENGTH)
+            .map(|i| (FloorTile::from_usize(i).sprite_pos(), i))
+            .collect(),
+        LayerKind::Wall | LayerKind::Front => (0..WallTile::LENGTH)
+            .map(|i| (WallTile::from_usize(i).sprite_pos(), i))
+            .collect(),
+        LayerKind::Terrain => HashMap::new(),
+    };
+
+    let mut tiles = Vec::with_capacity(gids.len());
+    for (i, &(gid, offset)) in gids.iter().enumerate() {
+        if gid == 0 {
+            tiles.push(0);
+            continue;
+        }
+
+        let found = lookup(tilesets, gid);
+        if let (Some((_, Some(props))), Some(collision), Some(encounter)) =
+            (found, data.collision.get_mut(i), data.encounter.get_mut(i))
+        {
+            match props.get("collision").map(|c| c.to_lowercase()).as_deref() {
+                Some("door") => *collision = 2,
+                Some("wall" | "true") => *collision = (*collision).max(1),
+                _ => {}
+            }
+            if let Some(kind) = props.get("encounter").filter(|kind| !kind.is_empty()) {
+                *encounter = Some(kind.clone());
+            }
+        }
+
+        let id = match kind {
+            LayerKind::Terrain => found
+                .and_then(|(_, props)| props?.get("terrain"))
+                .and_then(|terrain| parse_enum::<Terrain>(&terrain.to_lowercase()))
+                .map(|terrain| terrain as usize),
+            _ => found.and_then(|(pos, _)| ids.get(&pos).copied()),
+        };
+        match id {
+            Some(id) => tiles.push(id),
+            None => {
+                errors.push(MapError::InvalidTile {
+                    layer: name.clone(),
+                    row: i / width.max(1),
+                    column: i % width.max(1),
+                    value: format!("gid {}", gid),
+                    position: Some(text_pos(layer, offset)),
+                });
+                tiles.push(0);
+            }
+        }
+    }
+
+    Some(LayerData {
+        name,
+        kind,
+        tiles: tiles
+            .chunks(width.max(1))
+            .map(|row| row.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(" "))
+            .collect(),
+    })
+}
+
+//returns the layers and object groups of the map in order, including the ones inside groups
+//object groups inside tilesets hold the collision shapes of tiles, which are not part of the map
+fn map_layers<'a, 'input>(parent: Node<'a, 'input>) -> Vec<Node<'a, 'input>> {
+    parent
+        .children()
+        .flat_map(|node| match node.has_tag_name("group") {
+            true => map_layers(node),
+            false => vec![node],
+        })
+        .collect()
+}
+
+//builds encounter zones out of runs of tiles with the same encounter kind on each row
+//runs lined up on the rows below each other are merged, so a rectangle of grass is a single zone
+fn encounter_zones(encounter: &[Option<String>], width: usize, props: &Properties) -> Vec<EncounterZone> {
+    let mut zones: Vec<EncounterZone> = vec![];
+    for (i, kind) in encounter.iter().enumerate() {
+        let Some(kind) = kind else { continue };
+        let (x, y) = ((i % width) as i32, (i / width) as i32);
+
+        //extend the zone of the tile to the left if it is the same kind
+        if x > 0 && encounter[i - 1].as_ref() == Some(kind) {
+            if let Some(zone) = zones.last_mut() {
+                zone.width += 1;
+                continue;
+            }
+        }
+
+        let key = format!("encounters.{}", kind);
+        let strays = props
+            .get(&key)
+            .map(|list| {
+                list.split(',')
+                    .filter(|slot| !slot.trim().is_empty())
+                    .map(|slot| {
+                        let (species, weight) = slot.split_once(':').unwrap_or((slot, "1"));
+                        EncounterSlot {
+                            species: species.trim().to_string(),
+                            weight: weight.trim().parse().unwrap_or(1),
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let max_opponents = props
+            .get(&format!("{}.max_opponents", key))
+            .and_then(|max| max.parse().ok())
+            .unwrap_or(1);
+        let roaming = props
+            .get(&format!("{}.roaming", key))
+            .and_then(|roaming| roaming.parse().ok())
+            .unwrap_or(0);
+
+        zones.push(EncounterZone {
+            x,
+            y,
+            width: 1,
+            height: 1,
+            kind: kind.clone(),
+            strays,
+            max_opponents,
+            roaming,
+        });
+    }
+
+    let mut merged: Vec<EncounterZone> = vec![];
+    for zone in zones {
+        match merged.iter_mut().find(|above| {
+            above.kind == zone.kind
+                && above.x == zone.x
+                && above.width == zone.width
+                && above.y + above.height == zone.y
+        }) {
+            Some(above) => above.height += 1,
+            None => merged.push(zone),
+        }
+    }
+    merged
+}
+
+//reads one object, which either places the player spawn, an entity or a warp
+fn read_object(
+    object: Node,
+    tile_size: (f32, f32),
+    map: &mut MapFile,
+    errors: &mut Vec<MapError>,
+) {
+    let kind = object
+        .attribute("type")
+        .or_else(|| object.attribute("class"))
+        .unwrap_or("")
+        .to_lowercase();
+    let props = properties(object);
+    let location = format!("object {}", object.attribute("id").unwrap_or("?"));
+    let mut error = |message: String| {
+        errors.push(MapError::Invalid {
+            location: location.clone(),
+            message,
+        })
+    };
+
+    //objects made from tiles are positioned by their bottom left corner, everything else by the top left
+    let px = object.attribute("x").and_then(|x| x.parse::<f32>().ok()).unwrap_or(0.0);
+    let mut py = object.attribute("y").and_then(|y| y.parse::<f32>().ok()).unwrap_or(0.0);
+    if object.attribute("gid").is_some() {
+        py -= tile_size.1;
+    }
+    let (x, y) = ((px / tile_size.0).floor() as i32, (py / tile_size.1).floor() as i32);
+
+    let mut get = |name: &str| match props.get(name) {
+        Some(value) => Some(value.clone()),
+        None => {
+            error(format!("{} object is missing the \"{}\" property", kind, name));
+            None
+        }
+    };
+
+    match kind.as_str() {
+        "" => {}
+        "spawn" => map.spawn = Some((x, y)),
+        "npc" => {
+            //npcs that run a script when talked to don't need anything to say
+            let script = props.get("script").cloned();
+            let says = match script {
+                Some(_) => props.get("says").cloned().unwrap_or_default(),
+                None => {
+                    let Some(says) = get("says") else { return };
+                    says
+                }
+            };
+            let Some(sprite) = get("sprite") else { return };
+            let path = props
+                .get("path")
+                .map(|path| {
+                    path.split(',')
+                        .filter(|step| !step.trim().is_empty())
+                        .filter_map(parse_enum)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let facing = props.get("facing").and_then(|facing| parse_enum(facing));
+            //shop items are listed as "Item" or "Item:price", such as "Berry:20, CaptureCube"
+            let mut shop = vec![];
+            for entry in props.get("shop").map_or("", |shop| shop.as_str()).split(',') {
+                let mut parts = entry.split(':');
+                let name = parts.next().unwrap_or_default().trim();
+                if name.is_empty() {
+                    continue;
+                }
+                let Some(item) = parse_enum(name) else {
+                    error(format!("unknown item \"{}\"", name));
+                    continue;
+                };
+                let price = parts.next().and_then(|price| price.trim().parse().ok());
+                shop.push(ShopItem { item, price });
+            }
+            map.entities.push(EntitySpawn::Npc(Box::new(NpcSpawn {
+                x,
+                y,
+                sprite,
+                name: props
+                    .get("name")
+                    .cloned()
+                    .or_else(|| object.attribute("name").filter(|name| !name.is_empty()).map(String::from)),
+                says,
+                id: props.get("id").cloned(),
+                script,
+                condition: condition(&props),
+                path,
+                path_mode: props.get("path_mode").and_then(|mode| parse_enum(mode)).unwrap_or_default(),
+                facing,
+                wander: props.get("wander").and_then(|radius| radius.parse().ok()),
+                look_around: props.get("look_around").is_some_and(|look| look == "true"),
+                step_delay: props.get("step_delay").and_then(|delay| delay.parse().ok()),
+                shop,
+            })));
+        }
+        "item" => {
+            let Some(item) = get("item") else { return };
+            let Some(item) = parse_enum(&item) else {
+                error(format!("unknown item \"{}\"", item));
+                return;
+            };
+            let amount = props.get("amount").and_then(|a| a.parse().ok()).unwrap_or(1);
+            map.entities.push(EntitySpawn::Item { x, y, item, amount });
+        }
+        "sign" => {
+            let Some(says) = get("says") else { return };
+            map.entities.push(EntitySpawn::Sign { x, y, says });
+        }
+        "door" => map.entities.push(EntitySpawn::Door { x, y }),
+        "shrine" => map.entities.push(EntitySpawn::Shrine { x, y }),
+        "trigger" => {
+            let Some(script) = get("script") else { return };
+            //triggers cover the tiles of the object's rectangle
+            let size = |attribute: &str, tile_size: f32| {
+                object
+                    .attribute(attribute)
+                    .and_then(|size| size.parse::<f32>().ok())
+                    .map_or(1, |size| ((size / tile_size).round() as i32).max(1))
+            };
+            map.triggers.push(Trigger {
+                x,
+                y,
+                width: size("width", tile_size.0),
+                height: size("height", tile_size.1),
+                script,
+                once: props.get("once").is_some_and(|once| once == "true"),
+            });
+        }
+        "warp" => {
+            let (Some(target_map), Some(target_x), Some(target_y)) =
+                (get("target_map"), get("target_x"), get("target_y"))
+            else {
+                return;
+            };
+            let (Ok(target_map), Ok(target_x), Ok(target_y)) =
+                (target_map.parse(), target_x.parse(), target_y.parse())
+            else {
+                error(String::from("warp target_map, target_x and target_y have to be integers"));
+                return;
+            };
+            map.warps.push(Warp {
+                x,
+                y,
+                target_map,
+                target_x,
+                target_y,
+                facing: props.get("facing").and_then(|facing| parse_enum(facing)),
+                trigger: props
+                    .get("trigger")
+                    .and_then(|trigger| parse_enum(&trigger.to_lowercase()))
+                    .unwrap_or_default(),
+                condition: condition(&props),
+                locked_says: props.get("locked_says").cloned(),
+            });
+        }
+        _ => error(format!("unknown object type \"{}\"", kind)),
+    }
+}
+
+//reads a Tiled map into a map file
+//returns the map, or every problem found in it and its tilesets
+pub fn read_tmx(path: &Path) -> Result<MapFile, MapLoadError> {
+    let fail = |errors: Vec<MapError>| MapLoadError {
+        file: path.to_path_buf(),
+        errors,
+    };
+
+    let text = fs::read_to_string(path).map_err(|e| fail(vec![MapError::Io { message: e.to_string() }]))?;
+    let doc = Document::parse(&text).map_err(|e| fail(vec![syntax_error(&e)]))?;
+    let root = doc.root_element();
+    let folder = path.parent().unwrap_or(Path::new("."));
+    let mut errors = vec![];
+
+    let (width, height) = (number(root, "width") as usize, number(root, "height") as usize);
+    let tile_size = (number(root, "tilewidth").max(1) as f32, number(root, "tileheight").max(1) as f32);
+    let props = properties(root);
+
+    let tilesets: Vec<Tileset> = root
+        .children()
+        .filter(|child| child.has_tag_name("tileset"))
+        .filter_map(|node| read_tileset(node, folder).map_err(|e| errors.push(e)).ok())
+        .collect();
+
+    let mut map = MapFile {
+        name: props.get("name").cloned().unwrap_or_else(|| {
+            path.file_stem()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        }),
+        music: props.get("music").cloned(),
+        indoors: props.get("indoors").is_some_and(|indoors| indoors == "true"),
+        width,
+        height,
+        spawn: None,
+        layers: vec![],
+        collision: vec![],
+        encounter_zones: vec![],
+        warps: vec![],
+        entities: vec![],
+        triggers: vec![],
+    };
+
+    //layers and objects inside groups are read as if they were not grouped
+    let mut data = TileData {
+        collision: vec![0; width * height],
+        encounter: vec![None; width * height],
+    };
+    for node in map_layers(root) {
+        if node.has_tag_name("layer") {
+            if let Some(layer) = read_layer(node, &tilesets, width, &mut data, &mut errors) {
+                map.layers.push(layer);
+            }
+        } else if node.has_tag_name("objectgroup") {
+            for object in node.children().filter(|child| child.has_tag_name("object")) {
+                read_object(object, tile_size, &mut map, &mut errors);
+            }
+        }
+    }
+
+    map.collision = data
+        .collision
+        .chunks(width.max(1))
+        .map(|row| row.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(" "))
+        .collect();
+    map.encounter_zones = encounter_zones(&data.encounter, width, &props);
+
+    match errors.is_empty() {
+        true => Ok(map),
+        false => Err(fail(errors)),
+    }
+}
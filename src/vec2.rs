@@ -17,6 +17,18 @@ pub enum Direction {
     Right,
 }
 
+impl Direction {
+    //returns the direction facing the other way
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
 //compute the game direction between two vec2s for the purposes of calculating the correct moving velocity
 pub fn compute_direction(pos_from: Vec2, pos_to: Vec2) -> Direction {
     let dx = pos_to.0 - pos_from.0;
@@ -49,6 +61,18 @@ impl Vec2 {
         x as usize + y as usize * size_x
     }
 
+    //returns the position one tile away in a direction
+    #[inline]
+    pub fn step(self, direction: Direction) -> Vec2 {
+        let Vec2(x, y) = self;
+        match direction {
+            Direction::Up => Vec2(x, y - 1.0),
+            Direction::Down => Vec2(x, y + 1.0),
+            Direction::Left => Vec2(x - 1.0, y),
+            Direction::Right => Vec2(x + 1.0, y),
+        }
+    }
+
     //does a dist^2 calculation in case it's needed
     #[inline]
     #[allow(dead_code)]